@@ -0,0 +1,250 @@
+/*
+ * Part of mlat-client-rust (https://github.com/tjmullicani/mlat-client-rust) - an ADS-B multilateration client.
+ * Based on mlat-client (https://github.com/mutability/mlat-client)
+ * Copyright 2023, Timothy Mullican <timothy.j.mullican@gmail.com>
+ * Copyright 2015, Oliver Jowett <oliver@mutability.co.uk>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Parses AVR text frames, the `*<hex>;`/`@<timestamp><hex>;` line format
+//! emitted by dump1090's `--raw`/`--net-ro-port` output and by readsb, as
+//! an alternative to the binary Beast format the rest of this crate
+//! speaks. A leading `*` means no timestamp is present; a leading `@` is
+//! followed by a 12 hex digit (48-bit) MLAT timestamp before the payload.
+
+#[cfg(feature = "std")]
+use std::{fmt, string::String, string::ToString, vec::Vec};
+#[cfg(not(feature = "std"))]
+use alloc::{fmt, string::String, string::ToString, vec::Vec};
+
+use modes::modes_message::ModesMessage;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AvrError {
+    /// The line was empty (after trimming whitespace).
+    Empty,
+    /// The line didn't start with `*` or `@`.
+    UnknownPrefix(char),
+    /// The line didn't end with the `;` terminator.
+    MissingTerminator,
+    /// The hex payload (or, for `@` lines, the leading timestamp) wasn't
+    /// valid hex, or had an odd number of digits.
+    InvalidHex(String),
+    /// The hex payload decoded to bytes, but `ModesMessage::from_buffer`
+    /// rejected them.
+    Decode(String),
+}
+
+impl fmt::Display for AvrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AvrError::Empty => write!(f, "empty AVR line"),
+            AvrError::UnknownPrefix(c) => write!(f, "AVR line starts with `{}`, expected `*` or `@`", c),
+            AvrError::MissingTerminator => write!(f, "AVR line is missing its `;` terminator"),
+            AvrError::InvalidHex(s) => write!(f, "`{}` is not valid hex", s),
+            AvrError::Decode(message) => write!(f, "failed to decode Mode-S payload: {}", message),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AvrError {}
+
+// Length, in hex digits, of the `@` variant's leading MLAT timestamp.
+const TIMESTAMP_HEX_DIGITS: usize = 12;
+
+/// Where a `*`-prefixed AVR line's `timestamp` should come from, since the
+/// line itself carries none (unlike the `@` variant).
+#[derive(Debug, Clone, Copy)]
+pub enum TimestampSource {
+    /// Leave a timestamp-less frame's `timestamp` at `0`.
+    FromFrame,
+    /// Substitute `ticks` for a timestamp-less frame: a locally-captured
+    /// monotonic clock reading, in the same 12MHz-tick unit as a real
+    /// Beast timestamp. Multilateration timing degrades to purely local
+    /// timing when this is used, so callers should warn accordingly --
+    /// see [`line_has_timestamp`] to check up front whether it will be.
+    LocalMonotonic(u64),
+}
+
+/// Whether `line` carries its own MLAT timestamp (an `@`-prefixed AVR
+/// line) as opposed to none at all (`*`-prefixed). Lets a caller decide
+/// whether to warn about degraded sync quality before a
+/// [`TimestampSource::LocalMonotonic`] fallback is applied, without
+/// duplicating the rest of [`parse_avr_line`]'s parsing.
+pub fn line_has_timestamp(line: &str) -> bool {
+    line.trim().starts_with('@')
+}
+
+/// Parses one AVR-format line into a `ModesMessage`. Strips the `*`/`@`
+/// prefix and `;` terminator, hex-decodes the payload, and (for the `@`
+/// variant) the leading MLAT timestamp, then builds the message the same
+/// way a Beast frame's payload would be. A `*` line has no timestamp of
+/// its own; `timestamp_source` decides what `timestamp` it gets instead.
+/// If `trust_crc` is set, the message is built with
+/// `ModesMessage::from_buffer_trusted` instead of `from_buffer`, skipping
+/// CRC validity checks -- see that function's doc comment.
+pub fn parse_avr_line(line: &str, timestamp_source: TimestampSource, trust_crc: bool) -> Result<ModesMessage, AvrError> {
+    let line = line.trim();
+    let mut chars = line.chars();
+    let prefix = chars.next().ok_or(AvrError::Empty)?;
+    let has_timestamp = match prefix {
+        '*' => false,
+        '@' => true,
+        other => return Err(AvrError::UnknownPrefix(other)),
+    };
+
+    let body = line[prefix.len_utf8()..].strip_suffix(';').ok_or(AvrError::MissingTerminator)?;
+
+    let (timestamp_hex, payload_hex) = if has_timestamp {
+        if body.len() < TIMESTAMP_HEX_DIGITS {
+            return Err(AvrError::InvalidHex(body.to_string()));
+        }
+        body.split_at(TIMESTAMP_HEX_DIGITS)
+    } else {
+        ("", body)
+    };
+
+    let timestamp = if timestamp_hex.is_empty() {
+        match timestamp_source {
+            TimestampSource::FromFrame => 0,
+            TimestampSource::LocalMonotonic(ticks) => ticks,
+        }
+    } else {
+        u64::from_str_radix(timestamp_hex, 16).map_err(|_| AvrError::InvalidHex(timestamp_hex.to_string()))?
+    };
+
+    let data = hex_to_bytes(payload_hex).ok_or_else(|| AvrError::InvalidHex(payload_hex.to_string()))?;
+
+    if trust_crc {
+        ModesMessage::from_buffer_trusted(timestamp, 0, data).map_err(|e| AvrError::Decode(e.to_string()))
+    } else {
+        ModesMessage::from_buffer(timestamp, 0, data).map_err(|e| AvrError::Decode(e.to_string()))
+    }
+}
+
+// Decodes a hex string into bytes, or `None` if it has an odd number of
+// digits or contains anything other than hex digits.
+fn hex_to_bytes(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // DF11 AllCallReply, ICAO AB3D17 (from the adsb_deku test vectors).
+    const DF11_HEX: &str = "5DAB3D17D4BA29";
+
+    #[test]
+    fn parse_avr_line_decodes_a_star_frame_with_no_timestamp() {
+        let line = format!("*{};", DF11_HEX);
+
+        let message = parse_avr_line(&line, TimestampSource::FromFrame, false).expect("valid AVR line parses");
+
+        assert_eq!(message.timestamp, 0);
+        assert!(message.valid);
+        assert_eq!(message.address, 0xAB3D17);
+    }
+
+    #[test]
+    fn parse_avr_line_decodes_an_at_frame_with_a_leading_timestamp() {
+        let line = format!("@{:012X}{};", 0x123456789ABCu64, DF11_HEX);
+
+        let message = parse_avr_line(&line, TimestampSource::FromFrame, false).expect("valid AVR line parses");
+
+        assert_eq!(message.timestamp, 0x123456789ABC);
+        assert!(message.valid);
+        assert_eq!(message.address, 0xAB3D17);
+    }
+
+    #[test]
+    fn parse_avr_line_rejects_a_line_with_no_terminator() {
+        let line = format!("*{}", DF11_HEX);
+
+        assert_eq!(parse_avr_line(&line, TimestampSource::FromFrame, false), Err(AvrError::MissingTerminator));
+    }
+
+    #[test]
+    fn parse_avr_line_rejects_an_unknown_prefix() {
+        assert_eq!(parse_avr_line("#5DAB3D17D4BA29;", TimestampSource::FromFrame, false), Err(AvrError::UnknownPrefix('#')));
+    }
+
+    #[test]
+    fn parse_avr_line_rejects_non_hex_payload() {
+        assert!(matches!(parse_avr_line("*not_hex;", TimestampSource::FromFrame, false), Err(AvrError::InvalidHex(_))));
+    }
+
+    #[test]
+    fn parse_avr_line_trims_surrounding_whitespace() {
+        let line = format!("  *{};  \n", DF11_HEX);
+
+        assert!(parse_avr_line(&line, TimestampSource::FromFrame, false).is_ok());
+    }
+
+    #[test]
+    fn parse_avr_line_substitutes_a_local_monotonic_timestamp_for_a_star_frame() {
+        let line = format!("*{};", DF11_HEX);
+
+        let message = parse_avr_line(&line, TimestampSource::LocalMonotonic(0xABCD), false).expect("valid AVR line parses");
+
+        assert_eq!(message.timestamp, 0xABCD);
+    }
+
+    #[test]
+    fn parse_avr_line_prefers_an_at_frames_own_timestamp_over_the_fallback() {
+        let line = format!("@{:012X}{};", 0x123456789ABCu64, DF11_HEX);
+
+        let message = parse_avr_line(&line, TimestampSource::LocalMonotonic(0xABCD), false).expect("valid AVR line parses");
+
+        assert_eq!(message.timestamp, 0x123456789ABC);
+    }
+
+    #[test]
+    fn line_has_timestamp_distinguishes_star_and_at_lines() {
+        assert!(!line_has_timestamp(&format!("*{};", DF11_HEX)));
+        assert!(line_has_timestamp(&format!("@{:012X}{};", 0x123456789ABCu64, DF11_HEX)));
+    }
+
+    #[test]
+    fn to_avr_round_trips_an_at_frame_through_parse_avr_line() {
+        let line = format!("@{:012X}{};", 0x123456789ABCu64, DF11_HEX);
+        let message = parse_avr_line(&line, TimestampSource::FromFrame, false).expect("valid AVR line parses");
+
+        assert_eq!(message.to_avr(), line);
+    }
+
+    #[test]
+    fn to_avr_round_trips_a_star_frame_through_parse_avr_line() {
+        let line = format!("*{};", DF11_HEX);
+        let message = parse_avr_line(&line, TimestampSource::FromFrame, false).expect("valid AVR line parses");
+
+        assert_eq!(message.to_avr(), line);
+    }
+
+    #[test]
+    fn parse_avr_line_with_trust_crc_accepts_a_frame_with_a_corrupted_crc() {
+        // Flip enough of the trailing CRC bytes that the frame isn't just
+        // a correctable single/double-bit error.
+        let line = format!("*{}ffff;", &DF11_HEX[..DF11_HEX.len() - 4]);
+
+        let message = parse_avr_line(&line, TimestampSource::FromFrame, true).expect("trusted line should decode");
+
+        assert!(message.valid);
+    }
+}
@@ -0,0 +1,101 @@
+/*
+ * Part of mlat-client-rust (https://github.com/tjmullicani/mlat-client-rust) - an ADS-B multilateration client.
+ * Based on mlat-client (https://github.com/mutability/mlat-client)
+ * Copyright 2023, Timothy Mullican <timothy.j.mullican@gmail.com>
+ * Copyright 2015, Oliver Jowett <oliver@mutability.co.uk>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Benchmarks for the hot paths `modes_crc`/`modes_message` are optimized
+//! for: `checksum` (both message lengths) and the full `decode_message`
+//! path over a representative mix of downlink formats. Run with
+//! `cargo bench -p modes`. These are a number to watch when touching the
+//! table-driven CRC or `decode()`, not a correctness check -- see the
+//! `#[cfg(test)]` modules in `modes_crc.rs`/`modes_message.rs` for that.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use modes::modes_crc::{checksum, LONG_MSG_BITS, SHORT_MSG_BITS};
+use modes::modes_message::decode_message;
+
+// DF11 AllCallReply, ICAO ABCDEF, clean CRC -- a 7-byte short frame.
+fn short_frame() -> [u8; 7] {
+    let mut data = [0u8; 7];
+    data[0] = (11 << 3) | 5; // DF11, CA=5
+    data[1] = 0xab;
+    data[2] = 0xcd;
+    data[3] = 0xef;
+    let crc = checksum(&data, Some(SHORT_MSG_BITS));
+    data[4] = (crc >> 16) as u8;
+    data[5] = (crc >> 8) as u8;
+    data[6] = crc as u8;
+    data
+}
+
+// DF17 airborne position, ICAO ABCDEF, metype 11, clean CRC -- a 14-byte
+// long frame. Same construction `modes_message`'s `clean_df17_frame` test
+// fixture uses.
+fn long_frame() -> [u8; 14] {
+    let mut data = [0u8; 14];
+    data[0] = (17 << 3) | 5; // DF17, CA=5
+    data[1] = 0xab;
+    data[2] = 0xcd;
+    data[3] = 0xef;
+    data[4] = 11 << 3; // metype 11
+    data[6] = 0x00; // even CPR
+    data[7] = 0x12;
+    data[8] = 0x34;
+    data[9] = 0x56;
+    data[10] = 0x78;
+    let crc = checksum(&data, Some(LONG_MSG_BITS));
+    data[11] = (crc >> 16) as u8;
+    data[12] = (crc >> 8) as u8;
+    data[13] = crc as u8;
+    data
+}
+
+fn checksum_benchmarks(c: &mut Criterion) {
+    let short = short_frame();
+    let long = long_frame();
+
+    c.bench_function("checksum/short_msg", |b| {
+        b.iter(|| checksum(black_box(&short), Some(SHORT_MSG_BITS)))
+    });
+    c.bench_function("checksum/long_msg", |b| {
+        b.iter(|| checksum(black_box(&long), Some(LONG_MSG_BITS)))
+    });
+}
+
+fn decode_message_benchmarks(c: &mut Criterion) {
+    // A representative mix: DF11 (short, CRC-only), DF17 (long, position),
+    // and Mode-A/C (2 bytes, no CRC at all) -- the three shapes
+    // `decode_message` branches on internally.
+    let short = short_frame();
+    let long = long_frame();
+    let modeac: [u8; 2] = [0x12, 0x34];
+
+    c.bench_function("decode_message/df11", |b| {
+        b.iter(|| decode_message(black_box(0), black_box(0), black_box(&short)))
+    });
+    c.bench_function("decode_message/df17", |b| {
+        b.iter(|| decode_message(black_box(0), black_box(0), black_box(&long)))
+    });
+    c.bench_function("decode_message/mode_ac", |b| {
+        b.iter(|| decode_message(black_box(0), black_box(0), black_box(&modeac)))
+    });
+}
+
+criterion_group!(benches, checksum_benchmarks, decode_message_benchmarks);
+criterion_main!(benches);
@@ -0,0 +1,92 @@
+/*
+ * Part of mlat-client-rust (https://github.com/tjmullicani/mlat-client-rust) - an ADS-B multilateration client.
+ * Based on mlat-client (https://github.com/mutability/mlat-client)
+ * Copyright 2023, Timothy Mullican <timothy.j.mullican@gmail.com>
+ * Copyright 2015, Oliver Jowett <oliver@mutability.co.uk>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Geodesic distance/bearing helpers, shared by anything that needs to
+//! reason about how far apart two lat/lon points are (CPR range gating,
+//! coverage stats, ...), so the great-circle formulas only live in one
+//! place.
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Great-circle distance between two WGS84 points, in kilometres, via the
+/// haversine formula.
+pub fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let lat1r = lat1.to_radians();
+    let lat2r = lat2.to_radians();
+    let dlat = (lat2 - lat1).to_radians();
+    let dlon = (lon2 - lon1).to_radians();
+
+    let a = (dlat / 2.0).sin().powi(2) + lat1r.cos() * lat2r.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().atan2((1.0 - a).sqrt())
+}
+
+/// Initial compass bearing (0-360 degrees, 0 = north) from `(lat1, lon1)`
+/// to `(lat2, lon2)`, along the great circle connecting them.
+pub fn bearing_deg(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let lat1r = lat1.to_radians();
+    let lat2r = lat2.to_radians();
+    let dlon = (lon2 - lon1).to_radians();
+
+    let y = dlon.sin() * lat2r.cos();
+    let x = lat1r.cos() * lat2r.sin() - lat1r.sin() * lat2r.cos() * dlon.cos();
+    let bearing = y.atan2(x).to_degrees();
+
+    (bearing + 360.0) % 360.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // London to Paris, both distance and bearing widely published as
+    // reference values for great-circle formula sanity checks.
+    const LONDON: (f64, f64) = (51.5074, -0.1278);
+    const PARIS: (f64, f64) = (48.8566, 2.3522);
+
+    #[test]
+    fn haversine_km_matches_the_known_london_to_paris_distance() {
+        let distance = haversine_km(LONDON.0, LONDON.1, PARIS.0, PARIS.1);
+        assert!((distance - 344.0).abs() < 5.0, "expected ~344km, got {}", distance);
+    }
+
+    #[test]
+    fn haversine_km_is_zero_for_the_same_point() {
+        assert_eq!(haversine_km(LONDON.0, LONDON.1, LONDON.0, LONDON.1), 0.0);
+    }
+
+    #[test]
+    fn haversine_km_is_symmetric() {
+        let a_to_b = haversine_km(LONDON.0, LONDON.1, PARIS.0, PARIS.1);
+        let b_to_a = haversine_km(PARIS.0, PARIS.1, LONDON.0, LONDON.1);
+        assert!((a_to_b - b_to_a).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bearing_deg_points_roughly_southeast_from_london_to_paris() {
+        let bearing = bearing_deg(LONDON.0, LONDON.1, PARIS.0, PARIS.1);
+        assert!((bearing - 149.0).abs() < 2.0, "expected ~149 degrees, got {}", bearing);
+    }
+
+    #[test]
+    fn bearing_deg_points_due_north_for_a_pure_latitude_change() {
+        let bearing = bearing_deg(48.0, 2.0, 49.0, 2.0);
+        assert!(bearing.abs() < 1e-6, "expected ~0 degrees, got {}", bearing);
+    }
+}
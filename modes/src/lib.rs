@@ -18,11 +18,25 @@
  * along with this program.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use std::collections::HashMap;
-use std::cmp::Ordering;
-use std::fmt;
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use crate::modes::modes_message::*;
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+use crate::modes_message::*;
+
+// `modes_message` and `modes_crc` are the `no_std` + `alloc` decoding core
+// and build either way. `modes_cpr`, `geo`, and `modes_output` need a
+// hashed map / float trig / `String`s that aren't available off `std`, so
+// all three only build with the `std` feature (on by default).
+pub mod modes_message;
+pub mod modes_crc;
+#[cfg(feature = "std")]
+pub mod geo;
+#[cfg(feature = "std")]
+pub mod modes_cpr;
+#[cfg(feature = "std")]
+pub mod modes_output;
 
 // Special DF types for non-Mode-S messages
 pub const DF_MODEAC: u32 = 32;
@@ -30,6 +44,4 @@ pub const DF_EVENT_TIMESTAMP_JUMP: u32 = 33;
 pub const DF_EVENT_MODE_CHANGE: u32 = 34;
 pub const DF_EVENT_EPOCH_ROLLOVER: u32 = 35;
 pub const DF_EVENT_RADARCAPE_STATUS: u32 = 36;
-pub const DF_EVENT_RADARCAPE_POSITION: u32 = 37;
-
-//mod modes_message;
\ No newline at end of file
+pub const DF_EVENT_RADARCAPE_POSITION: u32 = 37;
\ No newline at end of file
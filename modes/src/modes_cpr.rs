@@ -0,0 +1,565 @@
+/*
+ * Part of mlat-client-rust (https://github.com/tjmullicani/mlat-client-rust) - an ADS-B multilateration client.
+ * Based on mlat-client (https://github.com/mutability/mlat-client)
+ * Copyright 2023, Timothy Mullican <timothy.j.mullican@gmail.com>
+ * Copyright 2015, Oliver Jowett <oliver@mutability.co.uk>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * References:
+ *   https://mode-s.org/decode/content/ads-b/3-airborne-position.html
+ *   https://mode-s.org/decode/book-the_1090mhz_riddle-junzi_sun.pdf
+ *   https://github.com/antirez/dump1090/
+ */
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use crate::geo::haversine_km;
+use crate::modes_message::ModesMessage;
+
+// Number of latitude zones used by the CPR scheme.
+const NZ: f64 = 15.0;
+
+// Default: a frame older than this (in seconds) is not paired with a newer
+// frame of the opposite parity when attempting globally-unambiguous
+// decoding, and is not used as "last known position" for local decoding.
+pub const DEFAULT_MAX_FRAME_AGE_SECS: f64 = 10.0;
+
+// Default: a decoded position farther than this from the receiver's own
+// location is rejected outright, see `within_range`. Local (non-global)
+// CPR decoding is only unambiguous within about half a latitude zone
+// (~300NM at the equator) of the reference position it's decoded against,
+// so a result well beyond that is a sign the reference was stale or the
+// two frames were mismatched, not a real aircraft position.
+pub const DEFAULT_MAX_RANGE_KM: f64 = 400.0;
+
+/// True if `(lat, lon)` is within `max_km` of `(ref_lat, ref_lon)`, via the
+/// haversine great-circle distance.
+pub fn within_range(lat: f64, lon: f64, ref_lat: f64, ref_lon: f64, max_km: f64) -> bool {
+    haversine_km(lat, lon, ref_lat, ref_lon) <= max_km
+}
+
+fn cpr_mod(a: f64, b: f64) -> f64 {
+    let r = a % b;
+    if r < 0.0 { r + b } else { r }
+}
+
+/// Number of longitude zones (NL) for a given latitude, per the CPR spec.
+/// Exposed publicly (rather than kept as a private decoding detail) so
+/// other code, and this module's own tests, can validate CPR behavior
+/// directly against the transition-latitude table without going through a
+/// full decode. Returns a value in `1..=59`.
+pub fn cpr_nl(lat: f64) -> u32 {
+    if lat == 0.0 {
+        return 59;
+    }
+    if lat.abs() >= 87.0 {
+        return if lat.abs() > 87.0 { 1 } else { 2 };
+    }
+
+    let a = 1.0 - (PI / (2.0 * NZ)).cos();
+    let b = (PI / 180.0 * lat).cos().powi(2);
+    ((2.0 * PI) / (1.0 - a / b).acos()).floor() as u32
+}
+
+/// A decoded position, with a horizontal containment radius (metres)
+/// derived from the NUCp of the frame(s) used to compute it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CprPosition {
+    pub lat: f64,
+    pub lon: f64,
+    pub nuc: u32,
+}
+
+// Falls back to "no containment guarantee" for a `nuc` outside the
+// defined table, same as this module did before `nucp_to_rc` moved the
+// table itself to `modes_message` so it could be reused outside of CPR
+// decoding (see e.g. `ModesMessage::nic`).
+fn nuc_radius_meters(nuc: u32) -> f64 {
+    crate::modes_message::nucp_to_rc(nuc).unwrap_or(f64::INFINITY)
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CprFrame {
+    lat_cpr: u32,
+    lon_cpr: u32,
+    nuc: u32,
+    timestamp: f64,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct KnownPosition {
+    lat: f64,
+    lon: f64,
+    timestamp: f64,
+}
+
+#[derive(Clone, Debug, Default)]
+struct AircraftState {
+    last_even: Option<CprFrame>,
+    last_odd: Option<CprFrame>,
+    last_position: Option<KnownPosition>,
+}
+
+/// Turns the even/odd CPR-encoded airborne position frames carried on
+/// `ModesMessage` into WGS84 fixes, per-aircraft.
+///
+/// Frames are paired up as they arrive: a matching even/odd pair within
+/// `max_frame_age` of each other is decoded unambiguously (no reference
+/// position needed); a lone frame falls back to locally-relative decoding
+/// against the aircraft's last known position, if one is still fresh.
+pub struct CprDecoder {
+    aircraft: HashMap<i32, AircraftState>,
+    max_frame_age: f64,
+    // The receiver's own location, and the range gate applied against it;
+    // `None` disables range gating entirely (the default), since not every
+    // caller has a receiver location to gate against.
+    receiver_location: Option<(f64, f64)>,
+    max_range_km: f64,
+}
+
+impl Default for CprDecoder {
+    fn default() -> Self {
+        CprDecoder::new()
+    }
+}
+
+impl CprDecoder {
+    pub fn new() -> Self {
+        CprDecoder::with_max_frame_age(DEFAULT_MAX_FRAME_AGE_SECS)
+    }
+
+    pub fn with_max_frame_age(max_frame_age: f64) -> Self {
+        CprDecoder {
+            aircraft: HashMap::new(),
+            max_frame_age,
+            receiver_location: None,
+            max_range_km: DEFAULT_MAX_RANGE_KM,
+        }
+    }
+
+    /// Rejects any position more than `max_range_km` from
+    /// `(ref_lat, ref_lon)` (see `within_range`), instead of returning it
+    /// from `update`. Local CPR decoding against a stale or mismatched
+    /// reference can otherwise produce a fix hundreds of km from reality;
+    /// gating against the receiver's known location catches that before it
+    /// reaches an mlat solve.
+    pub fn with_receiver_location(mut self, ref_lat: f64, ref_lon: f64, max_range_km: f64) -> Self {
+        self.receiver_location = Some((ref_lat, ref_lon));
+        self.max_range_km = max_range_km;
+        self
+    }
+
+    /// Feeds a decoded DF17/18 airborne position message in, keyed by its
+    /// ICAO address, and returns the resulting fix if one could be
+    /// computed. `now` is the current time in seconds, on the same clock
+    /// as `message.timestamp` (the caller is responsible for converting
+    /// from the 12MHz Beast timestamp if that is what it is using).
+    pub fn update(&mut self, message: &ModesMessage, now: f64) -> Option<CprPosition> {
+        if !message.even_cpr && !message.odd_cpr {
+            return None;
+        }
+
+        let frame = CprFrame {
+            lat_cpr: message.cpr_lat,
+            lon_cpr: message.cpr_lon,
+            nuc: message.nuc,
+            timestamp: now,
+        };
+
+        let max_frame_age = self.max_frame_age;
+        let receiver_location = self.receiver_location;
+        let max_range_km = self.max_range_km;
+        let state = self.aircraft.entry(message.address).or_default();
+        if message.even_cpr {
+            state.last_even = Some(frame);
+        } else {
+            state.last_odd = Some(frame);
+        }
+
+        let position = resolve(state, now, max_frame_age).filter(|fix| {
+            receiver_location
+                .map(|(ref_lat, ref_lon)| within_range(fix.lat, fix.lon, ref_lat, ref_lon, max_range_km))
+                .unwrap_or(true)
+        });
+        if let Some(fix) = position {
+            state.last_position = Some(KnownPosition { lat: fix.lat, lon: fix.lon, timestamp: now });
+        }
+        position
+    }
+
+    /// Discards per-aircraft state older than `max_frame_age`. Call this
+    /// periodically to bound memory use for aircraft that have gone out
+    /// of range.
+    pub fn expire(&mut self, now: f64) {
+        let max_frame_age = self.max_frame_age;
+        self.aircraft.retain(|_, state| {
+            if let Some(f) = state.last_even {
+                if now - f.timestamp > max_frame_age { state.last_even = None; }
+            }
+            if let Some(f) = state.last_odd {
+                if now - f.timestamp > max_frame_age { state.last_odd = None; }
+            }
+            if let Some(p) = state.last_position {
+                if now - p.timestamp > max_frame_age { state.last_position = None; }
+            }
+            state.last_even.is_some() || state.last_odd.is_some() || state.last_position.is_some()
+        });
+    }
+}
+
+fn resolve(state: &AircraftState, now: f64, max_frame_age: f64) -> Option<CprPosition> {
+    if let (Some(even), Some(odd)) = (state.last_even, state.last_odd) {
+        if (even.timestamp - odd.timestamp).abs() <= max_frame_age {
+            let newer_is_odd = odd.timestamp >= even.timestamp;
+            if let Some((lat, lon)) = decode_global_airborne(
+                even.lat_cpr, even.lon_cpr, odd.lat_cpr, odd.lon_cpr, newer_is_odd,
+            ) {
+                let nuc = if newer_is_odd { odd.nuc } else { even.nuc };
+                return Some(CprPosition { lat, lon, nuc });
+            }
+        }
+    }
+
+    // Fall back to locally-relative decoding of whichever frame is
+    // newest, against the last position we trust, as long as that
+    // reference position is still fresh.
+    let (newest, is_odd) = match (state.last_even, state.last_odd) {
+        (Some(e), Some(o)) if o.timestamp > e.timestamp => (o, true),
+        (Some(e), Some(_)) => (e, false),
+        (None, Some(o)) => (o, true),
+        (Some(e), None) => (e, false),
+        (None, None) => return None,
+    };
+
+    let reference = state.last_position?;
+    if now - reference.timestamp > max_frame_age {
+        return None;
+    }
+
+    let (lat, lon) = decode_local(newest.lat_cpr, newest.lon_cpr, reference.lat, reference.lon, is_odd);
+    Some(CprPosition { lat, lon, nuc: newest.nuc })
+}
+
+/// Globally-unambiguous CPR decoding of an even/odd frame pair. Returns
+/// `None` if the pair straddles a latitude zone boundary (the two frames
+/// disagree on `NL`) and cannot be combined.
+pub fn decode_global_airborne(
+    even_lat_cpr: u32,
+    even_lon_cpr: u32,
+    odd_lat_cpr: u32,
+    odd_lon_cpr: u32,
+    newer_is_odd: bool,
+) -> Option<(f64, f64)> {
+    let lat_even = even_lat_cpr as f64 / 131072.0;
+    let lat_odd = odd_lat_cpr as f64 / 131072.0;
+
+    let dlat_even = 360.0 / (4.0 * NZ);
+    let dlat_odd = 360.0 / (4.0 * NZ - 1.0);
+
+    let j = (59.0 * lat_even - 60.0 * lat_odd + 0.5).floor();
+
+    let mut rlat_even = dlat_even * (cpr_mod(j, 60.0) + lat_even);
+    let mut rlat_odd = dlat_odd * (cpr_mod(j, 59.0) + lat_odd);
+    if rlat_even >= 270.0 { rlat_even -= 360.0; }
+    if rlat_odd >= 270.0 { rlat_odd -= 360.0; }
+
+    if cpr_nl(rlat_even) != cpr_nl(rlat_odd) {
+        // The two frames span a latitude zone change; can't combine them.
+        return None;
+    }
+
+    let rlat = if newer_is_odd { rlat_odd } else { rlat_even };
+    let nl = cpr_nl(rlat);
+    let ni = (nl - if newer_is_odd { 1 } else { 0 }).max(1);
+
+    let lon_even = even_lon_cpr as f64 / 131072.0;
+    let lon_odd = odd_lon_cpr as f64 / 131072.0;
+    let m = (lon_even * (nl as f64 - 1.0) - lon_odd * nl as f64 + 0.5).floor();
+
+    let dlon = 360.0 / ni as f64;
+    let lon_cpr = if newer_is_odd { lon_odd } else { lon_even };
+    let mut rlon = dlon * (cpr_mod(m, ni as f64) + lon_cpr);
+    if rlon > 180.0 { rlon -= 360.0; }
+
+    Some((rlat, rlon))
+}
+
+/// Globally-unambiguous CPR decoding of an even/odd surface-position frame
+/// pair. Surface CPR halves the angular range relative to airborne CPR (90
+/// degrees rather than 360), which introduces a four-way quadrant
+/// ambiguity that this function does not attempt to resolve; callers who
+/// need an unambiguous surface fix should decode against a known receiver
+/// position with [`decode_cpr_local`] instead.
+pub fn decode_global_surface(
+    even_lat_cpr: u32,
+    even_lon_cpr: u32,
+    odd_lat_cpr: u32,
+    odd_lon_cpr: u32,
+    newer_is_odd: bool,
+) -> Option<(f64, f64)> {
+    let lat_even = even_lat_cpr as f64 / 131072.0;
+    let lat_odd = odd_lat_cpr as f64 / 131072.0;
+
+    let dlat_even = 90.0 / (4.0 * NZ);
+    let dlat_odd = 90.0 / (4.0 * NZ - 1.0);
+
+    let j = (59.0 * lat_even - 60.0 * lat_odd + 0.5).floor();
+
+    let rlat_even = dlat_even * (cpr_mod(j, 60.0) + lat_even);
+    let rlat_odd = dlat_odd * (cpr_mod(j, 59.0) + lat_odd);
+
+    if cpr_nl(rlat_even) != cpr_nl(rlat_odd) {
+        // The two frames span a latitude zone change; can't combine them.
+        return None;
+    }
+
+    let rlat = if newer_is_odd { rlat_odd } else { rlat_even };
+    let nl = cpr_nl(rlat);
+    let ni = (nl - if newer_is_odd { 1 } else { 0 }).max(1);
+
+    let lon_even = even_lon_cpr as f64 / 131072.0;
+    let lon_odd = odd_lon_cpr as f64 / 131072.0;
+    let m = (lon_even * (nl as f64 - 1.0) - lon_odd * nl as f64 + 0.5).floor();
+
+    let dlon = 90.0 / ni as f64;
+    let lon_cpr = if newer_is_odd { lon_odd } else { lon_even };
+    let rlon = dlon * (cpr_mod(m, ni as f64) + lon_cpr);
+
+    Some((rlat, rlon))
+}
+
+/// Locally-relative CPR decoding of a single frame against a known
+/// reference position. Valid as long as the reference is within ~1/2 of
+/// a latitude zone (roughly 300NM) of the aircraft's actual position.
+pub fn decode_local(lat_cpr: u32, lon_cpr: u32, ref_lat: f64, ref_lon: f64, is_odd: bool) -> (f64, f64) {
+    let dlat = if is_odd { 360.0 / (4.0 * NZ - 1.0) } else { 360.0 / (4.0 * NZ) };
+    let lat_cpr_f = lat_cpr as f64 / 131072.0;
+
+    let j = (ref_lat / dlat).floor() + (0.5 + cpr_mod(ref_lat, dlat) / dlat - lat_cpr_f).floor();
+    let rlat = dlat * (j + lat_cpr_f);
+
+    let nl = cpr_nl(rlat);
+    let ni = (nl - if is_odd { 1 } else { 0 }).max(1);
+    let dlon = 360.0 / ni as f64;
+    let lon_cpr_f = lon_cpr as f64 / 131072.0;
+
+    let m = (ref_lon / dlon).floor() + (0.5 + cpr_mod(ref_lon, dlon) / dlon - lon_cpr_f).floor();
+    let rlon = dlon * (m + lon_cpr_f);
+
+    (rlat, rlon)
+}
+
+/// Horizontal containment radius, in metres, implied by a fix's NUCp.
+pub fn containment_radius_meters(nuc: u32) -> f64 {
+    nuc_radius_meters(nuc)
+}
+
+/// Convenience wrapper around [`decode_global_airborne`]/
+/// [`decode_global_surface`] that reads the CPR components straight off a
+/// decoded even/odd `ModesMessage` pair instead of making the caller pull
+/// `cpr_lat`/`cpr_lon` out by hand. Which message is "newer" is decided by
+/// comparing `timestamp`.
+pub fn decode_cpr_global(even: &ModesMessage, odd: &ModesMessage, surface: bool) -> Option<(f64, f64)> {
+    let newer_is_odd = odd.timestamp >= even.timestamp;
+    if surface {
+        decode_global_surface(even.cpr_lat, even.cpr_lon, odd.cpr_lat, odd.cpr_lon, newer_is_odd)
+    } else {
+        decode_global_airborne(even.cpr_lat, even.cpr_lon, odd.cpr_lat, odd.cpr_lon, newer_is_odd)
+    }
+}
+
+/// Convenience wrapper around [`decode_local`] that reads the CPR
+/// components and parity straight off a decoded `ModesMessage`. Returns
+/// `None` if the message carries neither CPR flag (i.e. isn't a position
+/// message at all).
+pub fn decode_cpr_local(msg: &ModesMessage, ref_lat: f64, ref_lon: f64) -> Option<(f64, f64)> {
+    if !msg.even_cpr && !msg.odd_cpr {
+        return None;
+    }
+    Some(decode_local(msg.cpr_lat, msg.cpr_lon, ref_lat, ref_lon, msg.odd_cpr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Forward CPR encoding, the inverse of `decode_global_airborne`/
+    // `decode_local`, used only to build known-good test vectors.
+    fn encode_scaled(lat: f64, lon: f64, is_odd: bool, angle_range: f64) -> (u32, u32) {
+        let i = if is_odd { 1.0 } else { 0.0 };
+        let dlat = angle_range / (4.0 * NZ - i);
+        let lat_cpr = (131072.0 * (cpr_mod(lat, dlat) / dlat) + 0.5).floor() as u32 % 131072;
+
+        let nl = cpr_nl(lat);
+        let ni = (nl - is_odd as u32).max(1);
+        let dlon = angle_range / ni as f64;
+        let lon_cpr = (131072.0 * (cpr_mod(lon, dlon) / dlon) + 0.5).floor() as u32 % 131072;
+
+        (lat_cpr, lon_cpr)
+    }
+
+    fn encode(lat: f64, lon: f64, is_odd: bool) -> (u32, u32) {
+        encode_scaled(lat, lon, is_odd, 360.0)
+    }
+
+    #[test]
+    fn decodes_global_even_odd_pair() {
+        let (lat, lon) = (52.0, 4.0);
+        let (even_lat_cpr, even_lon_cpr) = encode(lat, lon, false);
+        let (odd_lat_cpr, odd_lon_cpr) = encode(lat, lon, true);
+
+        let (rlat, rlon) = decode_global_airborne(
+            even_lat_cpr, even_lon_cpr, odd_lat_cpr, odd_lon_cpr, true,
+        )
+        .expect("even/odd pair in the same NL zone should decode");
+
+        assert!((rlat - lat).abs() < 1e-3, "lat: expected {}, got {}", lat, rlat);
+        assert!((rlon - lon).abs() < 1e-3, "lon: expected {}, got {}", lon, rlon);
+    }
+
+    #[test]
+    fn decodes_global_even_odd_surface_pair() {
+        let (lat, lon) = (10.0, 20.0);
+        let (even_lat_cpr, even_lon_cpr) = encode_scaled(lat, lon, false, 90.0);
+        let (odd_lat_cpr, odd_lon_cpr) = encode_scaled(lat, lon, true, 90.0);
+
+        let (rlat, rlon) = decode_global_surface(
+            even_lat_cpr, even_lon_cpr, odd_lat_cpr, odd_lon_cpr, true,
+        )
+        .expect("even/odd surface pair in the same NL zone should decode");
+
+        assert!((rlat - lat).abs() < 1e-3, "lat: expected {}, got {}", lat, rlat);
+        assert!((rlon - lon).abs() < 1e-3, "lon: expected {}, got {}", lon, rlon);
+    }
+
+    fn position_message(cpr_lat: u32, cpr_lon: u32, odd: bool, timestamp: u64) -> ModesMessage {
+        let mut msg = ModesMessage::default();
+        msg.even_cpr = !odd;
+        msg.odd_cpr = odd;
+        msg.cpr_lat = cpr_lat;
+        msg.cpr_lon = cpr_lon;
+        msg.timestamp = timestamp;
+        msg
+    }
+
+    #[test]
+    fn decode_cpr_global_reads_cpr_fields_off_the_messages() {
+        let (lat, lon) = (52.0, 4.0);
+        let (even_lat_cpr, even_lon_cpr) = encode(lat, lon, false);
+        let (odd_lat_cpr, odd_lon_cpr) = encode(lat, lon, true);
+
+        let even = position_message(even_lat_cpr, even_lon_cpr, false, 0);
+        let odd = position_message(odd_lat_cpr, odd_lon_cpr, true, 1);
+
+        let (rlat, rlon) = decode_cpr_global(&even, &odd, false).expect("pair should decode");
+        assert!((rlat - lat).abs() < 1e-3);
+        assert!((rlon - lon).abs() < 1e-3);
+    }
+
+    #[test]
+    fn decode_cpr_local_uses_the_message_parity() {
+        let (lat, lon) = (52.0, 4.0);
+        let (lat_cpr, lon_cpr) = encode(lat, lon, false);
+        let msg = position_message(lat_cpr, lon_cpr, false, 0);
+
+        let (rlat, rlon) =
+            decode_cpr_local(&msg, lat - 0.01, lon - 0.01).expect("fresh reference should decode");
+        assert!((rlat - lat).abs() < 1e-3);
+        assert!((rlon - lon).abs() < 1e-3);
+    }
+
+    #[test]
+    fn decode_cpr_local_rejects_a_message_with_no_cpr_flags() {
+        let msg = ModesMessage::default();
+        assert!(decode_cpr_local(&msg, 52.0, 4.0).is_none());
+    }
+
+    #[test]
+    fn within_range_accepts_a_known_city_pair_inside_the_limit() {
+        // London to Paris is about 344km.
+        assert!(within_range(48.8566, 2.3522, 51.5074, -0.1278, 400.0));
+    }
+
+    #[test]
+    fn within_range_rejects_a_known_city_pair_beyond_the_limit() {
+        assert!(!within_range(48.8566, 2.3522, 51.5074, -0.1278, 300.0));
+    }
+
+    #[test]
+    fn cpr_decoder_accepts_a_global_fix_within_range_of_the_receiver() {
+        let (lat, lon) = (52.0, 4.0);
+        let (even_lat_cpr, even_lon_cpr) = encode(lat, lon, false);
+        let (odd_lat_cpr, odd_lon_cpr) = encode(lat, lon, true);
+
+        let mut decoder = CprDecoder::new().with_receiver_location(lat, lon, 50.0);
+        decoder.update(&position_message(even_lat_cpr, even_lon_cpr, false, 0), 0.0);
+        let position = decoder.update(&position_message(odd_lat_cpr, odd_lon_cpr, true, 1), 1.0);
+
+        assert!(position.is_some());
+    }
+
+    #[test]
+    fn cpr_decoder_rejects_a_global_fix_far_from_the_receiver() {
+        let (lat, lon) = (52.0, 4.0);
+        let (even_lat_cpr, even_lon_cpr) = encode(lat, lon, false);
+        let (odd_lat_cpr, odd_lon_cpr) = encode(lat, lon, true);
+
+        // New York, thousands of km from the decoded fix.
+        let mut decoder = CprDecoder::new().with_receiver_location(40.7128, -74.0060, DEFAULT_MAX_RANGE_KM);
+        decoder.update(&position_message(even_lat_cpr, even_lon_cpr, false, 0), 0.0);
+        let position = decoder.update(&position_message(odd_lat_cpr, odd_lon_cpr, true, 1), 1.0);
+
+        assert!(position.is_none());
+    }
+
+    #[test]
+    fn cpr_nl_is_59_at_the_equator() {
+        assert_eq!(cpr_nl(0.0), 59);
+    }
+
+    #[test]
+    fn cpr_nl_is_59_just_below_the_first_transition_latitude() {
+        assert_eq!(cpr_nl(10.0), 59);
+    }
+
+    #[test]
+    fn cpr_nl_drops_to_58_just_above_the_first_transition_latitude() {
+        assert_eq!(cpr_nl(10.4705), 58);
+    }
+
+    #[test]
+    fn cpr_nl_is_2_at_and_just_below_87_degrees() {
+        assert_eq!(cpr_nl(86.9), 2);
+        assert_eq!(cpr_nl(87.0), 2);
+    }
+
+    #[test]
+    fn cpr_nl_is_1_beyond_87_degrees() {
+        assert_eq!(cpr_nl(87.1), 1);
+        assert_eq!(cpr_nl(89.9), 1);
+    }
+
+    #[test]
+    fn cpr_nl_is_1_at_the_poles() {
+        assert_eq!(cpr_nl(90.0), 1);
+        assert_eq!(cpr_nl(-90.0), 1);
+    }
+
+    #[test]
+    fn cpr_nl_is_symmetric_across_the_equator() {
+        assert_eq!(cpr_nl(51.0), cpr_nl(-51.0));
+    }
+}
@@ -0,0 +1,613 @@
+/*
+ * Part of mlat-client-rust (https://github.com/tjmullicani/mlat-client-rust) - an ADS-B multilateration client.
+ * Based on mlat-client (https://github.com/mutability/mlat-client)
+ * Copyright 2023, Timothy Mullican <timothy.j.mullican@gmail.com>
+ * Copyright 2015, Oliver Jowett <oliver@mutability.co.uk>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * References:
+ *   https://docs.rs/crc/latest/crc/struct.Algorithm.html
+ *   https://stackoverflow.com/a/44560366
+ *   https://llllllllll.github.io/c-extension-tutorial/appendix.html
+ *   https://www.codeconvert.ai/c-to-rust-converter
+ *   https://thepythoncode.com/assistant/code-converter/rust/
+ *   https://godbolt.org/
+ *   https://mode-s.org/decode/content/ads-b/8-error-control.html
+ *   https://github.com/antirez/dump1090/
+ *   https://github.com/flightrac/modes-crc/
+ */
+
+extern crate hex_slice;
+extern crate crc;
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crc::{Crc, Algorithm};
+use hex_slice::AsHex;
+use log::{debug, error, trace};
+
+pub const MODES_GENERATOR_POLY: u32 = 0x1FFF409;
+pub const LONG_MSG_BITS: u8         = 112;
+pub const SHORT_MSG_BITS: u8        = 56;
+
+// Default cap on how many bits decode() will try to flip to recover a
+// frame. Two-bit correction is considerably more expensive (it probes
+// every bit against the single-bit table) and more prone to false
+// positives on short (DF11) frames, so callers that want it have to ask.
+pub const DEFAULT_MAX_CORRECTABLE_BITS: u8 = 1;
+
+/* Parity table for MODE S Messages.
+ * The table contains 112 elements, every element corresponds to a bit set
+ * in the message, starting from the first bit of actual data after the
+ * preamble.
+ *
+ * For messages of 112 bit, the whole table is used.
+ * For messages of 56 bits only the last 56 elements are used.
+ *
+ * The algorithm is as simple as xoring all the elements in this table
+ * for which the corresponding bit on the message is set to 1.
+ *
+ * The latest 24 elements in this table are set to 0 as the checksum at the
+ * end of the message should not affect the computation.
+ *
+ * Note: this function can be used with DF11 and DF17, other modes have
+ * the CRC xored with the sender address as they are reply to interrogations,
+ * but a casual listener can't split the address from the checksum.
+ */
+static PARITY_TABLE: [u32; 112] = [
+    0x3935ea, 0x1c9af5, 0xf1b77e, 0x78dbbf, 0xc397db, 0x9e31e9, 0xb0e2f0, 0x587178,
+    0x2c38bc, 0x161c5e, 0x0b0e2f, 0xfa7d13, 0x82c48d, 0xbe9842, 0x5f4c21, 0xd05c14,
+    0x682e0a, 0x341705, 0xe5f186, 0x72f8c3, 0xc68665, 0x9cb936, 0x4e5c9b, 0xd8d449,
+    0x939020, 0x49c810, 0x24e408, 0x127204, 0x093902, 0x049c81, 0xfdb444, 0x7eda22,
+    0x3f6d11, 0xe04c8c, 0x702646, 0x381323, 0xe3f395, 0x8e03ce, 0x4701e7, 0xdc7af7,
+    0x91c77f, 0xb719bb, 0xa476d9, 0xadc168, 0x56e0b4, 0x2b705a, 0x15b82d, 0xf52612,
+    0x7a9309, 0xc2b380, 0x6159c0, 0x30ace0, 0x185670, 0x0c2b38, 0x06159c, 0x030ace,
+    0x018567, 0xff38b7, 0x80665f, 0xbfc92b, 0xa01e91, 0xaff54c, 0x57faa6, 0x2bfd53,
+    0xea04ad, 0x8af852, 0x457c29, 0xdd4410, 0x6ea208, 0x375104, 0x1ba882, 0x0dd441,
+    0xf91024, 0x7c8812, 0x3e4409, 0xe0d800, 0x706c00, 0x383600, 0x1c1b00, 0x0e0d80,
+    0x0706c0, 0x038360, 0x01c1b0, 0x00e0d8, 0x00706c, 0x003836, 0x001c1b, 0xfff409,
+    0x000000, 0x000000, 0x000000, 0x000000, 0x000000, 0x000000, 0x000000, 0x000000,
+    0x000000, 0x000000, 0x000000, 0x000000, 0x000000, 0x000000, 0x000000, 0x000000,
+    0x000000, 0x000000, 0x000000, 0x000000, 0x000000, 0x000000, 0x000000, 0x000000
+];
+
+// Xors together the `PARITY_TABLE` entries for every set bit in `data`,
+// the syndrome-computation core shared by `checksum` and
+// `checksum_compare`'s expected-value loop. `offset` shifts into
+// `PARITY_TABLE` to line up a short (56-bit) frame with the table's
+// last 56 entries; long frames pass an offset of 0.
+fn parity(data: &[u8], bits: usize, offset: usize) -> u32 {
+    let mut crc = 0;
+    for j in 0..bits {
+        let b = j / 8;
+        let bit = j % 8;
+        let bitmask = 1 << (7 - bit);
+
+        if data.get(b).map_or(false, |&byte| byte & bitmask != 0) {
+            crc ^= PARITY_TABLE[j + offset];
+        }
+    }
+    crc
+}
+
+// Calculates the checksum of the data frame passed to it, based on the parity table provided.
+// It takes a byte slice `data` and an optional number of bits.
+// If the number of bits is not provided, it is determined based on the length of `data`.
+pub fn checksum(data: &[u8], bits: Option<u8>) -> u32 {
+    let bits = match bits {
+        Some(b) => b as usize,
+        None => {
+            if data.len() * 8 == SHORT_MSG_BITS as usize {
+                SHORT_MSG_BITS as usize
+            } else if data.len() * 8 == LONG_MSG_BITS as usize {
+                LONG_MSG_BITS as usize
+            } else {
+                return 0 as u32;
+            }
+        }
+    };
+    debug!("checksum: bits = {}", bits);
+
+    let offset = if bits == LONG_MSG_BITS as usize {
+        0
+    } else {
+        (LONG_MSG_BITS - SHORT_MSG_BITS) as usize
+    };
+
+    parity(data, bits, offset)
+}
+
+// Precomputed per-byte contribution to `checksum`: `table[position][value]`
+// is the XOR of the `PARITY_TABLE` entries for whichever bits of `value`
+// are set, at byte `position` of a 112-bit (long) frame. A short (56-bit)
+// frame's byte `b` uses the same bits of `PARITY_TABLE` as a long frame's
+// byte `b + 7` (both `checksum`'s and this table's `offset`/`position`
+// line up 56-bit frames with the table's last 56 entries), so one set of
+// 14 tables covers both frame sizes; `checksum_fast` just picks a
+// different starting position.
+fn build_byte_table(position: usize) -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut value = 0usize;
+    while value < 256 {
+        let mut crc = 0;
+        for bit in 0..8 {
+            if value & (1 << (7 - bit)) != 0 {
+                crc ^= PARITY_TABLE[position * 8 + bit];
+            }
+        }
+        table[value] = crc;
+        value += 1;
+    }
+    table
+}
+
+fn build_byte_tables() -> [[u32; 256]; 14] {
+    let mut tables = [[0u32; 256]; 14];
+    for (position, table) in tables.iter_mut().enumerate() {
+        *table = build_byte_table(position);
+    }
+    tables
+}
+
+#[cfg(feature = "std")]
+static BYTE_TABLES: OnceLock<[[u32; 256]; 14]> = OnceLock::new();
+
+#[cfg(feature = "std")]
+fn byte_tables() -> &'static [[u32; 256]; 14] {
+    BYTE_TABLES.get_or_init(build_byte_tables)
+}
+
+// Without `std` there is no portable one-time-init primitive (see
+// `build_syndrome_table`'s comment for the same tradeoff); rebuilding 14
+// small tables per call is still far cheaper than the bit-at-a-time loop
+// it replaces.
+#[cfg(not(feature = "std"))]
+fn byte_tables() -> [[u32; 256]; 14] {
+    build_byte_tables()
+}
+
+/// Table-driven equivalent of [`checksum`], for a high-rate feeder where
+/// testing 112 (or 56) individual bits per message is a measurable cost.
+/// XORs one precomputed per-byte table entry per byte of `data` instead;
+/// same signature, same result.
+pub fn checksum_fast(data: &[u8], bits: Option<u8>) -> u32 {
+    let bits = match bits {
+        Some(b) => b as usize,
+        None => {
+            if data.len() * 8 == SHORT_MSG_BITS as usize {
+                SHORT_MSG_BITS as usize
+            } else if data.len() * 8 == LONG_MSG_BITS as usize {
+                LONG_MSG_BITS as usize
+            } else {
+                return 0;
+            }
+        }
+    };
+
+    let start = if bits == LONG_MSG_BITS as usize { 0 } else { 7 };
+    let tables = byte_tables();
+
+    let mut crc = 0;
+    for b in 0..bits / 8 {
+        if let Some(&byte) = data.get(b) {
+            crc ^= tables[start + b][byte as usize];
+        }
+    }
+    crc
+}
+
+/// The outcome of comparing a frame's transmitted checksum against the
+/// expected one, from [`checksum_compare`]. Distinguishes a clean frame
+/// from one with a specific, potentially-correctable syndrome, and both
+/// from a `data`/`bits` combination `checksum_compare` couldn't even
+/// evaluate -- collapsing all three into a `bool` (as `checksum_compare`
+/// used to) left error-correction callers unable to tell "definitely no
+/// error" from "this length isn't supported".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrcResult {
+    /// The received checksum matched the expected one exactly.
+    Clean,
+    /// The received checksum didn't match; `syndrome` is the two xored
+    /// together, i.e. the CRC of whatever error pattern was introduced
+    /// (see [`crc_residual`], which computes the same value directly).
+    /// A caller doing bit-flip correction looks this up in a
+    /// precomputed single-bit-error table.
+    Mismatch { syndrome: u32 },
+    /// `data`/`bits` wasn't a supported message length (not 56 or 112
+    /// bits), so no comparison could be made.
+    LengthInvalid,
+}
+
+// Calculates the checksum of the data frame passed to it, based on the parity table provided.
+// It takes a byte slice `data` and an optional number of bits.
+// If the number of bits is not provided, it is determined based on the length of `data`.
+// Returns a `CrcResult` describing whether the checksum in the message (last 3 bytes) matches
+// the computed checksum, and if not, the syndrome of the mismatch.
+pub fn checksum_compare(data: &[u8], bits: Option<u8>) -> CrcResult {
+    let bits = match bits {
+        Some(b) => b as usize,
+        None => {
+            if data.len() * 8 == SHORT_MSG_BITS as usize {
+                SHORT_MSG_BITS as usize
+            } else if data.len() * 8 == LONG_MSG_BITS as usize {
+                LONG_MSG_BITS as usize
+            } else {
+                return CrcResult::LengthInvalid;
+            }
+        }
+    };
+    trace!("checksum_compare: bits = {}", bits);
+
+    let offset = if bits == LONG_MSG_BITS as usize {
+        0
+    } else {
+        LONG_MSG_BITS - SHORT_MSG_BITS
+    };
+
+    trace!("checksum_compare: offset = {}", offset);
+    let received_checksum = modescrc_buffer_crc(data, Some(bits));
+
+    let expected_checksum = parity(data, bits, offset as usize);
+    trace!("checksum_compare: expected_checksum = {:#02X}", expected_checksum);
+    trace!("checksum_compare: received_checksum = {:#02X}", received_checksum);
+
+    if received_checksum == expected_checksum {
+        CrcResult::Clean
+    } else {
+        CrcResult::Mismatch { syndrome: received_checksum ^ expected_checksum }
+    }
+}
+
+// Extracts the CRC value from a data frame last 3 bytes.
+// It takes a byte slice `data` and an optional number of bits.
+// If the number of bits is not provided, it defaults to the length of `data` multiplied by 8 (to convert to bits).
+pub fn modescrc_buffer_crc(data: &[u8], bits: Option<usize>) -> u32 {
+    let bytes = bits.map_or(data.len() * 8, |b| b as usize) / 8;
+
+    // Ensure that there are enough bytes in the data slice to prevent panic due to out-of-bounds access.
+    trace!("crc: bytes = {}", bytes);
+    if bytes < 3 {
+        error!("Data slice is too short to calculate CRC");
+        return 0;
+    }
+
+    trace!("crc: data = {:#02X}", data.as_hex());
+    ((data[bytes - 3] as u32) << 16) | ((data[bytes - 2] as u32) << 8) | (data[bytes - 1] as u32)
+}
+
+// Computes the CRC residual (syndrome) of a frame: the checksum computed
+// over the payload, xored with the CRC value transmitted in the last 3
+// bytes. Because the CRC is linear, this residual is exactly the CRC of
+// whatever error pattern was introduced in transit: zero if the frame is
+// clean, the single-bit-flip syndrome if one bit was corrupted, and (for
+// DF0/4/5/20/21, whose parity field is xored with the sender's ICAO
+// address rather than transmitted bare) the ICAO address itself.
+pub fn crc_residual(data: &[u8], bits: Option<u8>) -> u32 {
+    let bits = match bits {
+        Some(b) => b,
+        None => {
+            if data.len() * 8 == SHORT_MSG_BITS as usize {
+                SHORT_MSG_BITS
+            } else if data.len() * 8 == LONG_MSG_BITS as usize {
+                LONG_MSG_BITS
+            } else {
+                return 0;
+            }
+        }
+    };
+
+    checksum(data, Some(bits)) ^ modescrc_buffer_crc(data, Some(bits as usize))
+}
+
+/// Recovers the ICAO address overlaid on the CRC field of a Mode-S reply
+/// (DF 0/4/5/16/20/21/24). These transponders XOR their address into the
+/// parity field instead of transmitting a bare CRC, so the residual
+/// computed by [`crc_residual`] is the address itself for an
+/// otherwise-clean frame. Returns `None` if `data` is not exactly `bits`
+/// long.
+pub fn recover_address(data: &[u8], bits: u8) -> Option<u32> {
+    if data.len() * 8 != bits as usize {
+        return None;
+    }
+    Some(crc_residual(data, Some(bits)))
+}
+
+// Building a syndrome table computes the CRC of a message with exactly
+// one bit set for every bit position in the frame, so the syndrome of a
+// received frame can be looked up directly to find the bit that was
+// flipped.
+//
+// With `std`, the short- and long-frame tables are built once (behind a
+// `OnceLock`) and reused; without it there is no portable one-time-init
+// primitive available, so `no_std` builds just rebuild the (112-entry,
+// at most) table on every call. That is cheap enough next to a CRC
+// correction pass that it isn't worth pulling in an embedded-friendly
+// lazy-init crate for.
+#[cfg(feature = "std")]
+static SYNDROME_TABLE_SHORT: OnceLock<HashMap<u32, usize>> = OnceLock::new();
+#[cfg(feature = "std")]
+static SYNDROME_TABLE_LONG: OnceLock<HashMap<u32, usize>> = OnceLock::new();
+
+#[cfg(feature = "std")]
+fn build_syndrome_table(bits: u8) -> HashMap<u32, usize> {
+    let mut table = HashMap::with_capacity(bits as usize);
+    let mut probe = vec![0u8; bits as usize / 8];
+    for i in 0..bits as usize {
+        probe[i / 8] = 1 << (7 - (i % 8));
+        let syndrome = checksum(&probe, Some(bits));
+        if syndrome != 0 {
+            table.insert(syndrome, i);
+        }
+        probe[i / 8] = 0;
+    }
+    table
+}
+
+#[cfg(not(feature = "std"))]
+fn build_syndrome_table(bits: u8) -> BTreeMap<u32, usize> {
+    let mut table = BTreeMap::new();
+    let mut probe = vec![0u8; bits as usize / 8];
+    for i in 0..bits as usize {
+        probe[i / 8] = 1 << (7 - (i % 8));
+        let syndrome = checksum(&probe, Some(bits));
+        if syndrome != 0 {
+            table.insert(syndrome, i);
+        }
+        probe[i / 8] = 0;
+    }
+    table
+}
+
+#[cfg(feature = "std")]
+fn syndrome_table(bits: u8) -> &'static HashMap<u32, usize> {
+    if bits == SHORT_MSG_BITS {
+        SYNDROME_TABLE_SHORT.get_or_init(|| build_syndrome_table(SHORT_MSG_BITS))
+    } else {
+        SYNDROME_TABLE_LONG.get_or_init(|| build_syndrome_table(LONG_MSG_BITS))
+    }
+}
+
+#[cfg(feature = "std")]
+fn lookup_syndrome(bits: u8, syndrome: u32) -> Option<usize> {
+    syndrome_table(bits).get(&syndrome).copied()
+}
+
+#[cfg(not(feature = "std"))]
+fn lookup_syndrome(bits: u8, syndrome: u32) -> Option<usize> {
+    build_syndrome_table(bits).get(&syndrome).copied()
+}
+
+// Flips bit `i` (counting from the first bit of `data`) in a buffer of
+// the given bit length. Unlike `PARITY_TABLE`, which is a flat 112-entry
+// table shared between short and long frames, `i` here is already an
+// index into `data` itself (it comes from `build_syndrome_table`, whose
+// probe buffer is sized to exactly `bits` bits) — no offset applies.
+fn flip_bit(data: &mut [u8], _bits: u8, i: usize) {
+    data[i / 8] ^= 1 << (7 - (i % 8));
+}
+
+/// Corrects a single-bit error in `data` using the CRC syndrome: computes
+/// the residual, looks it up in the syndrome table built from
+/// `PARITY_TABLE`, and flips the implicated bit if found. Returns the
+/// corrected bit position (relative to the start of the frame), or `None`
+/// if the frame was already clean or the residual doesn't match any
+/// single-bit-flip syndrome.
+///
+/// This is the single-bit case of [`correct_errors`], narrowed for
+/// callers that only want that specific correction and a plain bit
+/// position rather than a `Vec`.
+pub fn fix_single_bit_error(data: &mut [u8], bits: u8) -> Option<usize> {
+    let residual = crc_residual(data, Some(bits));
+    if residual == 0 {
+        return None;
+    }
+
+    let i = lookup_syndrome(bits, residual)?;
+    flip_bit(data, bits, i);
+    debug!("fix_single_bit_error: corrected single-bit error at position {}", i);
+    Some(i)
+}
+
+/// Attempts to correct up to `max_bits` bit errors in `data` using the CRC
+/// syndrome, for frames whose parity is not overlaid with the sender's
+/// address (DF11 with a zero IID, DF17, DF18). On success, returns the bit
+/// positions (relative to the start of the frame) that were flipped; `data`
+/// is modified in place. Returns `None` if the residual could not be
+/// resolved to a correctable error within `max_bits` flips.
+///
+/// `max_bits` of 1 only attempts single-bit correction via direct syndrome
+/// lookup. `max_bits` of 2 additionally tries every candidate first bit,
+/// flipping it and checking whether the residual left over is itself a
+/// single-bit syndrome.
+pub fn correct_errors(data: &mut [u8], bits: u8, max_bits: u8) -> Option<Vec<usize>> {
+    let residual = crc_residual(data, Some(bits));
+    if residual == 0 {
+        return Some(Vec::new());
+    }
+    if max_bits == 0 {
+        return None;
+    }
+
+    if let Some(i) = lookup_syndrome(bits, residual) {
+        flip_bit(data, bits, i);
+        debug!("correct_errors: corrected single-bit error at position {}", i);
+        return Some(vec![i]);
+    }
+
+    if max_bits >= 2 {
+        let nbits = bits as usize;
+        for i in 0..nbits {
+            flip_bit(data, bits, i);
+            let residual_after = crc_residual(data, Some(bits));
+            if let Some(j) = lookup_syndrome(bits, residual_after) {
+                if j != i {
+                    flip_bit(data, bits, j);
+                    debug!("correct_errors: corrected two-bit error at positions {} and {}", i, j);
+                    return Some(vec![i, j]);
+                }
+            }
+            flip_bit(data, bits, i);
+        }
+    }
+
+    error!("correct_errors: unable to resolve residual {:#06x} within {} bit(s)", residual, max_bits);
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::TryInto;
+
+    // DF11, CA=5, ICAO 0xABCDEF, with the CRC field filled in so the
+    // frame is clean (IID folded into the residual as zero).
+    fn clean_df11_frame() -> [u8; 7] {
+        let mut data = [0x2cu8, 0xab, 0xcd, 0xef, 0x00, 0x00, 0x00];
+        let crc = checksum(&data, Some(SHORT_MSG_BITS));
+        data[4] = (crc >> 16) as u8;
+        data[5] = (crc >> 8) as u8;
+        data[6] = crc as u8;
+        data
+    }
+
+    #[test]
+    fn corrects_single_bit_error_in_df11_frame() {
+        let clean = clean_df11_frame();
+        assert_eq!(crc_residual(&clean, Some(SHORT_MSG_BITS)), 0);
+
+        let mut corrupted = clean;
+        corrupted[2] ^= 1 << 3;
+        assert_ne!(corrupted, clean);
+
+        let flipped = correct_errors(&mut corrupted, SHORT_MSG_BITS, DEFAULT_MAX_CORRECTABLE_BITS);
+        assert_eq!(flipped, Some(vec![20]));
+        assert_eq!(corrupted, clean);
+    }
+
+    #[test]
+    fn fix_single_bit_error_corrects_a_flipped_bit() {
+        let clean = clean_df11_frame();
+        let mut corrupted = clean;
+        corrupted[2] ^= 1 << 3;
+
+        let position = fix_single_bit_error(&mut corrupted, SHORT_MSG_BITS);
+        assert_eq!(position, Some(20));
+        assert_eq!(corrupted, clean);
+    }
+
+    #[test]
+    fn fix_single_bit_error_returns_none_for_an_already_clean_frame() {
+        let mut clean = clean_df11_frame();
+        assert_eq!(fix_single_bit_error(&mut clean, SHORT_MSG_BITS), None);
+    }
+
+    #[test]
+    fn recover_address_reads_the_overlaid_icao_address_off_a_clean_reply() {
+        // All payload bits zero, so `checksum` (which only sees bits
+        // 0..32 for a short frame) is zero and the residual is exactly
+        // the overlaid address in the last 3 bytes.
+        let data = [0x00u8, 0x00, 0x00, 0x00, 0xab, 0xcd, 0xef];
+        assert_eq!(recover_address(&data, SHORT_MSG_BITS), Some(0xabcdef));
+    }
+
+    #[test]
+    fn recover_address_rejects_the_wrong_length() {
+        assert_eq!(recover_address(&[0u8; 6], SHORT_MSG_BITS), None);
+    }
+
+    // A small xorshift PRNG, since this crate takes no dependency on
+    // `rand`: deterministic test data is enough here, we just need many
+    // distinct payloads.
+    fn xorshift(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    #[test]
+    fn checksum_fast_matches_checksum_across_random_payloads() {
+        let mut state = 0x1234_5678_9abc_def0u64;
+        for _ in 0..2000 {
+            let mut long = [0u8; 14];
+            for byte in long.iter_mut() {
+                *byte = xorshift(&mut state) as u8;
+            }
+            assert_eq!(
+                checksum_fast(&long, Some(LONG_MSG_BITS)),
+                checksum(&long, Some(LONG_MSG_BITS)),
+            );
+
+            let short: [u8; 7] = long[..7].try_into().unwrap();
+            assert_eq!(
+                checksum_fast(&short, Some(SHORT_MSG_BITS)),
+                checksum(&short, Some(SHORT_MSG_BITS)),
+            );
+        }
+    }
+
+    #[test]
+    fn checksum_matches_the_parity_core_checksum_compare_uses() {
+        let clean = clean_df11_frame();
+        assert_eq!(
+            checksum(&clean, Some(SHORT_MSG_BITS)),
+            parity(&clean, SHORT_MSG_BITS as usize, (LONG_MSG_BITS - SHORT_MSG_BITS) as usize),
+        );
+    }
+
+    #[test]
+    fn checksum_compare_reports_clean_for_an_unmodified_frame() {
+        let clean = clean_df11_frame();
+        assert_eq!(checksum_compare(&clean, Some(SHORT_MSG_BITS)), CrcResult::Clean);
+    }
+
+    #[test]
+    fn checksum_compare_reports_the_mismatch_syndrome_for_a_corrupted_frame() {
+        let mut corrupted = clean_df11_frame();
+        corrupted[1] ^= 1 << 2;
+
+        match checksum_compare(&corrupted, Some(SHORT_MSG_BITS)) {
+            CrcResult::Mismatch { syndrome } => assert_ne!(syndrome, 0),
+            other => panic!("expected Mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn checksum_compare_reports_length_invalid_for_an_unsupported_length() {
+        let data = [0u8; 5];
+        assert_eq!(checksum_compare(&data, None), CrcResult::LengthInvalid);
+    }
+
+    #[test]
+    fn fix_single_bit_error_returns_none_for_an_unresolvable_residual() {
+        let mut corrupted = clean_df11_frame();
+        corrupted[1] ^= 1 << 2;
+        corrupted[3] ^= 1 << 5;
+
+        assert_eq!(fix_single_bit_error(&mut corrupted, SHORT_MSG_BITS), None);
+    }
+}
@@ -0,0 +1,3083 @@
+/*
+ * Part of mlat-client-rust (https://github.com/tjmullicani/mlat-client-rust) - an ADS-B multilateration client.
+ * Based on mlat-client (https://github.com/mutability/mlat-client)
+ * Copyright 2023, Timothy Mullican <timothy.j.mullican@gmail.com>
+ * Copyright 2015, Oliver Jowett <oliver@mutability.co.uk>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ * References:
+ *   https://www.codeconvert.ai/c-to-rust-converter
+ *   https://thepythoncode.com/assistant/code-converter/rust/
+ *   https://mode-s.org/decode/content/ads-b/8-error-control.html
+ *   https://mode-s.org/decode/book-the_1090mhz_riddle-junzi_sun.pdf
+ *
+ * This module is the `no_std` + `alloc` decoding core: it only depends on
+ * pure computation over the message bytes, so it also builds (without
+ * `std`) for bare-metal receivers. The `std`-only `cmp`/`fmt` traits used
+ * here are re-exported identically by `core`, so only the collection type
+ * needs to switch with the `std` feature.
+ */
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+use std::{collections::BTreeMap, format, string::{String, ToString}, vec::Vec};
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, format, string::{String, ToString}, vec::Vec};
+
+use core::cmp::Ordering;
+use core::convert::TryInto;
+use core::fmt;
+use hex_slice::AsHex;
+
+use crate::modes_crc;
+use crate::{
+    DF_EVENT_EPOCH_ROLLOVER, DF_EVENT_MODE_CHANGE, DF_EVENT_RADARCAPE_POSITION,
+    DF_EVENT_RADARCAPE_STATUS, DF_EVENT_TIMESTAMP_JUMP, DF_MODEAC,
+};
+
+/// Event data carried by the special `DF_EVENT_*` pseudo-messages. A
+/// small tagged union rather than a plain string, so numeric event
+/// fields (a timestamp delta, a DIP-switch byte) round-trip without a
+/// parse step at the consumer.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EventData {
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+// Serializes `ModesMessage::data` as a lowercase hex string (e.g. "8d4840d6")
+// rather than a JSON array of bytes, so fixtures logged with `--output` are
+// readable and diffable.
+#[cfg(feature = "serde")]
+mod hex_data {
+    use super::{format, String, Vec};
+    use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &Vec<u8>, serializer: S) -> Result<S::Ok, S::Error> {
+        let hex: String = data.iter().map(|b| format!("{:02x}", b)).collect();
+        hex.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        if hex.len() % 2 != 0 {
+            return Err(D::Error::custom("hex string has an odd number of digits"));
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(D::Error::custom))
+            .collect()
+    }
+}
+
+/// Decodes altitude information from a compact binary format (AC13 format).
+///
+/// # Arguments
+///
+/// * `ac13` - An unsigned integer representing the encoded altitude.
+///
+/// # Returns
+///
+/// Returns an Option<i32> representing the decoded altitude in feet, or None if the input is invalid.
+pub fn decode_ac13(ac13: u32) -> Option<i32> {
+    let mut h = 0;
+    let mut f = 0;
+    //let mut a;
+
+    // Check if ac13 is zero or if the M bit (bit 6) is set
+    if ac13 == 0 || (ac13 & 0x0040) != 0 {
+        return None;
+    }
+
+    // Check if the Q bit (bit 4) is set
+    if (ac13 & 0x0010) != 0 {
+        // Calculate altitude using a different encoding scheme
+        let n = ((ac13 & 0x1F80) >> 2) | ((ac13 & 0x0020) >> 1) | (ac13 & 0x000F);
+        let altitude = (n as i32) * 25 - 1000;
+        return Some(altitude);
+    }
+
+    // Decode altitude using the Gillham code (Gray code)
+    // Check for illegal Gillham code
+    if (ac13 & 0x1500) == 0 {
+        return None;
+    }
+
+    // Calculate the hundreds (h) and the fractional part (f) of the altitude
+    if ac13 & 0x1000 != 0 { h ^= 7; } // C1
+    if ac13 & 0x0400 != 0 { h ^= 3; } // C2
+    if ac13 & 0x0100 != 0 { h ^= 1; } // C4
+
+    if h & 5 != 0 {
+        h ^= 5;
+    }
+
+    if h > 5 {
+        // Illegal value for h
+        return None;
+    }
+
+    if ac13 & 0x0010 != 0 { f ^= 0x1ff; } // D1
+    if ac13 & 0x0004 != 0 { f ^= 0x0ff; } // D2
+    if ac13 & 0x0001 != 0 { f ^= 0x07f; } // D4
+    if ac13 & 0x0800 != 0 { f ^= 0x03f; } // A1
+    if ac13 & 0x0200 != 0 { f ^= 0x01f; } // A2
+    if ac13 & 0x0080 != 0 { f ^= 0x00f; } // A4
+    if ac13 & 0x0020 != 0 { f ^= 0x007; } // B1
+    if ac13 & 0x0008 != 0 { f ^= 0x003; } // B2
+    if ac13 & 0x0002 != 0 { f ^= 0x001; } // B4
+    /*if ac13 & 0x0800 != 0 { f ^= 0x03f; } // A1
+    if ac13 & 0x0200 != 0 { f ^= 0x01f; } // A2
+    if ac13 & 0x0080 != 0 { f ^= 0x00f; } // A4
+    if ac13 & 0x0020 != 0 { f ^= 0x007; } // B1
+    if ac13 & 0x0008 != 0 { f ^= 0x003; } // B2
+    if ac13 & 0x0002 != 0 { f ^= 0x001; } // B4*/
+
+    if f & 1 != 0 {
+        h = 6 - h;
+    }
+
+    // Calculate the altitude
+    //let a = 500 * (f as i32) + 100 * (h as i32) - 1300;
+    let a = 500 * f + 100 * h - 1300;
+    if a < -1200 {
+        // Illegal altitude value
+        return None;
+    }
+
+    // Return the decoded altitude
+    return Some(a);
+}
+
+/// Helper function to decode altitude information from a compact binary format (AC12 format).
+///
+/// # Arguments
+///
+/// * `ac12` - An unsigned integer representing the encoded altitude in AC12 format.
+///
+/// # Returns
+///
+/// Returns an Option<i32> representing the decoded altitude in feet, or None if the input is invalid.
+pub fn decode_ac12(ac12: u32) -> Option<i32> {
+    // Reformat the bits to match the AC13 format
+    let ac13 = ((ac12 & 0x0fc0) << 1) | (ac12 & 0x003f);
+    // Call decode_ac13 to do the actual decoding
+    decode_ac13(ac13)
+}
+
+/// Decodes the VS (vertical status), SL (ACAS sensitivity level), and RI
+/// (reply information / airspeed capability) fields shared by DF0 (Short
+/// Air-Air Surveillance) and DF16 (Long Air-Air Surveillance); both share
+/// the same first 4 bytes regardless of total message length.
+///
+/// # Arguments
+///
+/// * `data` - The message's first 4 bytes, i.e. `message.data[0..4]`.
+///
+/// # Returns
+///
+/// `(on_ground, sensitivity_level, reply_information)`: `on_ground` is the
+/// VS bit (`true` if the transmitting aircraft reports itself on the
+/// ground), `sensitivity_level` is ACAS's 3-bit SL field (`0` if ACAS is
+/// inoperative), and `reply_information` is the 4-bit RI field (airspeed
+/// capability if ACAS has issued a resolution advisory, `0` if ACAS is
+/// inoperative).
+pub fn decode_short_air_air_status(data: &[u8]) -> (bool, u8, u8) {
+    let on_ground = data[0] & 0x04 != 0;
+    let sensitivity_level = (data[1] >> 5) & 0x07;
+    let reply_information = ((data[1] & 0x07) << 1) | ((data[2] >> 7) & 1);
+    (on_ground, sensitivity_level, reply_information)
+}
+
+/// Extracts the II/SI interrogator code embedded in a DF11 all-call
+/// reply's parity overlay. A clean DF11 frame's CRC residual is 0 when
+/// the interrogator used IC (interrogator code) 0, or the II/SI code
+/// itself (1..=15 for II, 16..=63 for SI) in its low 7 bits otherwise;
+/// this is what lets a radar site attribute a reply to the interrogation
+/// that triggered it instead of treating every DF11 as anonymous.
+///
+/// # Arguments
+///
+/// * `data` - The full DF11 message buffer, i.e. `message.data`.
+///
+/// # Returns
+///
+/// `None` if the residual's low 7 bits are 0 (IC 0, nothing to report);
+/// `Some(code)` otherwise.
+pub fn df11_interrogator(data: &[u8]) -> Option<u8> {
+    let bits = if data.len() * 8 >= modes_crc::LONG_MSG_BITS as usize {
+        modes_crc::LONG_MSG_BITS
+    } else {
+        modes_crc::SHORT_MSG_BITS
+    };
+    match (modes_crc::crc_residual(data, Some(bits)) & 0x7f) as u8 {
+        0 => None,
+        code => Some(code),
+    }
+}
+
+/// Decodes the 13-bit Mode-S identity (squawk) field carried by DF5/DF21
+/// surveillance replies into its 4-digit octal display value (e.g. `7500`
+/// for the hijack emergency code), by demultiplexing the standard A/B/C/D
+/// pulse encoding.
+///
+/// # Arguments
+///
+/// * `data` - The 2 bytes containing the ID13 field, i.e. `message.data[2..4]`.
+///
+/// # Returns
+///
+/// The decoded squawk, with each of its 4 octal digits held in its own
+/// decimal place (e.g. `7500`, not `0o7500`).
+pub fn decode_identity(data: &[u8]) -> u16 {
+    let id13 = ((data[0] & 0x1f) as u16) << 8 | data[1] as u16;
+
+    let c1 = (id13 >> 12) & 1;
+    let a1 = (id13 >> 11) & 1;
+    let c2 = (id13 >> 10) & 1;
+    let a2 = (id13 >> 9) & 1;
+    let c4 = (id13 >> 8) & 1;
+    let a4 = (id13 >> 7) & 1;
+    let b1 = (id13 >> 5) & 1;
+    let d1 = (id13 >> 4) & 1;
+    let b2 = (id13 >> 3) & 1;
+    let d2 = (id13 >> 2) & 1;
+    let b4 = (id13 >> 1) & 1;
+    let d4 = id13 & 1;
+
+    let a = (a4 << 2) | (a2 << 1) | a1;
+    let b = (b4 << 2) | (b2 << 1) | b1;
+    let c = (c4 << 2) | (c2 << 1) | c1;
+    let d = (d4 << 2) | (d2 << 1) | d1;
+
+    a * 1000 + b * 100 + c * 10 + d
+}
+
+// The 6-bit IA-5 subset (ICAO Annex 10 Vol IV) used to pack flight IDs
+// into identification messages; index 0 and the gaps are reserved codes
+// with no assigned character.
+const CALLSIGN_CHARSET: &[u8; 64] =
+    b"?ABCDEFGHIJKLMNOPQRSTUVWXYZ????? ???????????????0123456789??????";
+
+/// Decodes the 8-character flight ID packed into an identification
+/// message's ME field (metype 1-4).
+///
+/// # Arguments
+///
+/// * `data` - The 7-byte ME field, i.e. `message.data[4..11]`.
+///
+/// # Returns
+///
+/// The callsign with trailing spaces trimmed, or `None` if `data` is the
+/// wrong length or any of the 8 packed 6-bit codes is a reserved value.
+pub fn decode_callsign(data: &[u8]) -> Option<String> {
+    if data.len() != 7 {
+        return None;
+    }
+
+    let chars1 = ((data[1] as u32) << 16) | ((data[2] as u32) << 8) | data[3] as u32;
+    let chars2 = ((data[4] as u32) << 16) | ((data[5] as u32) << 8) | data[6] as u32;
+    let codes = [
+        (chars1 >> 18) & 0x3f,
+        (chars1 >> 12) & 0x3f,
+        (chars1 >> 6) & 0x3f,
+        chars1 & 0x3f,
+        (chars2 >> 18) & 0x3f,
+        (chars2 >> 12) & 0x3f,
+        (chars2 >> 6) & 0x3f,
+        chars2 & 0x3f,
+    ];
+
+    let mut callsign = String::with_capacity(8);
+    for code in codes {
+        callsign.push(CALLSIGN_CHARSET[code as usize] as char);
+    }
+
+    let trimmed = callsign.trim_end_matches(' ');
+    if trimmed.is_empty() || trimmed.contains('?') {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+/// Decodes the vertical rate carried by an airborne velocity message (ME
+/// type 19); the field is encoded the same way for all four subtypes.
+///
+/// # Arguments
+///
+/// * `data` - The 7-byte ME field, i.e. `message.data[4..11]`.
+///
+/// # Returns
+///
+/// The vertical rate in feet per minute (positive is climbing), or `None`
+/// if the field is the standard "not available" encoding of zero.
+pub fn decode_vertical_rate(data: &[u8]) -> Option<i32> {
+    if data.len() != 7 {
+        return None;
+    }
+
+    let raw = (((data[4] & 0x07) as i32) << 6) | ((data[5] >> 2) as i32);
+    if raw == 0 {
+        return None;
+    }
+
+    let mut rate = (raw - 1) * 64;
+    if data[4] & 0x08 != 0 {
+        rate = -rate;
+    }
+    Some(rate)
+}
+
+/// Decodes ground speed (in knots) and track (in degrees, `0..360`) from
+/// an airborne velocity message (ME type 19), subtypes 1-2
+/// (GNSS/INS-referenced ground velocity). Subtypes 3-4
+/// (airspeed-referenced) are not handled and return `None`.
+///
+/// Requires the `std` feature, since deriving a track angle from the
+/// velocity components needs `atan2`, which `core` does not provide.
+///
+/// # Arguments
+///
+/// * `data` - The 7-byte ME field, i.e. `message.data[4..11]`.
+#[cfg(feature = "std")]
+pub fn decode_ground_velocity(data: &[u8]) -> Option<(u16, f64)> {
+    if data.len() != 7 {
+        return None;
+    }
+
+    let subtype = data[0] & 0x07;
+    if subtype != 1 && subtype != 2 {
+        return None;
+    }
+
+    let ew_raw = (((data[1] & 0x03) as i32) << 8) | data[2] as i32;
+    let ns_raw = (((data[3] & 0x7f) as i32) << 3) | (((data[4] & 0xe0) >> 5) as i32);
+    if ew_raw == 0 || ns_raw == 0 {
+        // "no velocity information" for that axis
+        return None;
+    }
+
+    let scale = if subtype == 2 { 4 } else { 1 }; // subtype 2 is supersonic
+    let mut ew_vel = (ew_raw - 1) * scale;
+    let mut ns_vel = (ns_raw - 1) * scale;
+
+    let ground_speed = ((ns_vel * ns_vel + ew_vel * ew_vel) as f64).sqrt();
+
+    if data[1] & 0x04 != 0 {
+        ew_vel = -ew_vel;
+    }
+    if data[3] & 0x80 != 0 {
+        ns_vel = -ns_vel;
+    }
+    let mut track = (ew_vel as f64).atan2(ns_vel as f64) * 180.0 / std::f64::consts::PI;
+    if track < 0.0 {
+        track += 360.0;
+    }
+
+    Some((ground_speed.round() as u16, track))
+}
+
+/// Decodes the 7-bit `MOV` (movement) field of a surface position message
+/// (ME type 5-8) into a ground speed in knots, per the DO-260 piecewise
+/// table.
+///
+/// # Arguments
+///
+/// * `movement` - The raw 7-bit `MOV` field (only the low 7 bits are used).
+///
+/// # Returns
+///
+/// The ground speed in knots, or `None` if the value is the standard "not
+/// available" encoding (0) or one of the reserved values (125-127).
+pub fn decode_surface_movement(movement: u8) -> Option<f64> {
+    match movement {
+        0 => None,
+        1 => Some(0.0),
+        2..=8 => Some(0.125 + (movement - 2) as f64 * 0.125),
+        9..=12 => Some(1.0 + (movement - 9) as f64 * 0.25),
+        13..=38 => Some(2.0 + (movement - 13) as f64 * 0.5),
+        39..=93 => Some(15.0 + (movement - 39) as f64),
+        94..=108 => Some(70.0 + (movement - 94) as f64 * 2.0),
+        109..=123 => Some(100.0 + (movement - 109) as f64 * 5.0),
+        124 => Some(175.0),
+        _ => None,
+    }
+}
+
+// Table of 95% horizontal containment radii (metres) indexed by NUCp, per
+// DO-260. NUCp 0 carries no meaningful containment guarantee.
+const NUCP_CONTAINMENT_RADIUS_METERS: [f64; 10] = [
+    f64::INFINITY, 185200.0, 92600.0, 46300.0, 18520.0,
+    9260.0, 3704.0, 1852.0, 926.0, 185.2,
+];
+
+/// Looks up the 95% horizontal containment radius (Rc, in metres) implied
+/// by a decoded NUCp value, per the DO-260 table. Returns `None` for a
+/// `nuc` outside the defined `0..=9` range, so callers can distinguish
+/// "unknown/invalid category" from NUCp 0's "no containment guarantee"
+/// (`Some(f64::INFINITY)`).
+///
+/// For ADS-B version 0 emitters (the only version this decoder
+/// distinguishes), NIC and NUCp are drawn from the same table, so
+/// [`ModesMessage::nic`] is just this function applied to `self.nuc`.
+pub fn nucp_to_rc(nuc: u32) -> Option<f64> {
+    NUCP_CONTAINMENT_RADIUS_METERS.get(nuc as usize).copied()
+}
+
+// See modes_crc::crc_residual() for the live implementation used by decode().
+
+// Returns the event name associated with a given DF event code.
+pub fn df_event_name(df: u32) -> Option<String> {
+    match df {
+        DF_EVENT_TIMESTAMP_JUMP => Some("DF_EVENT_TIMESTAMP_JUMP".to_string()),
+        DF_EVENT_MODE_CHANGE => Some("DF_EVENT_MODE_CHANGE".to_string()),
+        DF_EVENT_EPOCH_ROLLOVER => Some("DF_EVENT_EPOCH_ROLLOVER".to_string()),
+        DF_EVENT_RADARCAPE_STATUS => Some("DF_EVENT_RADARCAPE_STATUS".to_string()),
+        _ => None,
+    }
+}
+
+/// A short human-readable description of `df`, covering every downlink
+/// format `decode()` recognizes as well as the synthetic `DF_MODEAC`/
+/// `DF_EVENT_*` values, for tooling and UIs that want to label a message
+/// by its `df` without duplicating this table. Unlike [`df_event_name`],
+/// which only names the synthetic event codes, this covers standard DFs
+/// too and always returns something -- an unrecognized `df` gets a
+/// generic "reserved/unknown" description rather than `None`.
+pub fn df_description(df: u32) -> &'static str {
+    match df {
+        0 => "DF0: Short Air-Air Surveillance (ACAS)",
+        4 => "DF4: Surveillance, Altitude Reply",
+        5 => "DF5: Surveillance, Identity Reply",
+        11 => "DF11: All-Call Reply",
+        16 => "DF16: Long Air-Air Surveillance (ACAS)",
+        17 => "DF17: Extended Squitter (ADS-B)",
+        18 => "DF18: Extended Squitter, Non-Transponder (TIS-B/ADS-R)",
+        19 => "DF19: Extended Squitter, Military Application",
+        20 => "DF20: Comm-B, Altitude Reply",
+        21 => "DF21: Comm-B, Identity Reply",
+        24 => "DF24: Comm-D, Extended Length Message",
+        DF_MODEAC => "Mode A/C reply",
+        DF_EVENT_TIMESTAMP_JUMP => "Event: receiver timestamp jumped",
+        DF_EVENT_MODE_CHANGE => "Event: receiver Mode A/C/S mode changed",
+        DF_EVENT_EPOCH_ROLLOVER => "Event: receiver 48-bit timestamp counter rolled over",
+        DF_EVENT_RADARCAPE_STATUS => "Event: Radarcape status message",
+        DF_EVENT_RADARCAPE_POSITION => "Event: Radarcape GPS position message",
+        _ => "Reserved or unknown downlink format",
+    }
+}
+
+// internal entry point to build a new message from a buffer
+pub fn modesmessage_from_buffer(timestamp: u64, signal: u8, data: Vec<u8>, datalen: usize) -> ModesMessage {
+    let copydata = data;
+
+    let mut message = ModesMessage::default();
+    message.timestamp = timestamp;
+    message.signal = signal;
+    message.data = copydata;
+
+    message
+}
+
+/// Why decoding a payload failed, returned by [`ModesMessage::from_buffer`]
+/// and [`decode_message`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The payload length doesn't match what its Downlink Format expects:
+    /// 2 bytes for Mode-A/C, 7 for a short (`DF < 16`) reply, or 14 for a
+    /// long (`DF >= 16`) reply.
+    WrongLength { len: usize },
+    /// The Downlink Format isn't one this decoder handles (`0, 4, 5, 11,
+    /// 16, 17, 18, 19, 20, 21`).
+    UnknownDf { df: u32 },
+    /// The frame's CRC residual didn't check out and couldn't be repaired.
+    CrcFailed,
+    /// The altitude subfield was present (non-zero) but didn't decode to a
+    /// legal Gillham/Q-bit encoding.
+    InvalidAltitude,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::WrongLength { len } => {
+                write!(f, "payload length {} does not match its Downlink Format", len)
+            }
+            DecodeError::UnknownDf { df } => write!(f, "unhandled Downlink Format {}", df),
+            DecodeError::CrcFailed => write!(f, "CRC residual did not check out and could not be corrected"),
+            DecodeError::InvalidAltitude => write!(f, "altitude subfield is not a legal encoding"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Decodes a single Mode S/Mode-A/C payload, the way `libbeast`/`avr` frame
+/// readers hand frames off to the rest of the pipeline: on top of
+/// [`ModesMessage::from_buffer`]'s length/Downlink-Format checks, this also
+/// rejects a frame whose CRC didn't check out or whose altitude subfield is
+/// present but undecodable — giving callers a single `Result` to match on
+/// instead of having to inspect `valid`/`altitude` on a decoded message.
+pub fn decode_message(timestamp: u64, signal: u8, payload: &[u8]) -> Result<ModesMessage, DecodeError> {
+    let message = ModesMessage::from_buffer(timestamp, signal, payload.to_vec())?;
+
+    if message.datalen == 2 {
+        // Mode-A/C reply: from_buffer() always accepts these.
+        return Ok(message);
+    }
+    if !message.valid {
+        return Err(DecodeError::CrcFailed);
+    }
+    if has_invalid_altitude_encoding(&message) {
+        return Err(DecodeError::InvalidAltitude);
+    }
+
+    Ok(message)
+}
+
+/// Decodes a batch of raw frames -- each a `(timestamp, signal, payload)`
+/// triple, the same arguments [`decode_message`] takes -- in parallel with
+/// rayon, since decoding one frame never depends on another. Errors are
+/// per-frame, not fatal to the batch: the result at index `i` is exactly
+/// what `decode_message(frames[i].0, frames[i].1, &frames[i].2)` would
+/// have returned. Needs `std` (rayon's thread pool doesn't exist in
+/// `no_std`), gated behind the `parallel` feature so a `no_std`/embedded
+/// build never pulls rayon in.
+#[cfg(all(feature = "std", feature = "parallel"))]
+pub fn decode_batch(frames: &[(u64, u8, Vec<u8>)]) -> Vec<Result<ModesMessage, DecodeError>> {
+    use rayon::prelude::*;
+    frames.par_iter().map(|(timestamp, signal, payload)| decode_message(*timestamp, *signal, payload)).collect()
+}
+
+/// What [`diagnose`] found out about a payload, whether or not it would
+/// decode. Unlike [`DecodeError`], this is computed eagerly for every
+/// payload -- including ones that decode fine -- so a caller debugging a
+/// receiver that's silently dropping frames can log one struct and see
+/// exactly which check, if any, failed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameDiagnosis {
+    /// The Downlink Format `data[0]`'s top 5 bits decode to, or
+    /// [`DF_MODEAC`] for a 2-byte Mode-A/C reply. `None` if `data` was too
+    /// short to have a first byte at all.
+    pub df: Option<u32>,
+    /// The payload length `df` expects (2 for Mode-A/C, 7 for `df < 16`,
+    /// 14 otherwise). `None` if `df` couldn't be determined.
+    pub expected_len: Option<usize>,
+    /// `data.len()`, regardless of whether it matched `expected_len`.
+    pub actual_len: usize,
+    /// Whether `df` is one [`ModesMessage::decode`] knows how to handle.
+    pub df_supported: bool,
+    /// The CRC residual (see [`modes_crc::crc_residual`]), computed only
+    /// once `actual_len` matches `expected_len` -- a short or truncated
+    /// buffer doesn't have a complete parity field to check.
+    pub crc_syndrome: Option<u32>,
+    /// A human-readable explanation of the first problem found, or that
+    /// the frame decodes cleanly.
+    pub reason: String,
+}
+
+/// Runs every check [`decode_message`] would, but always returns a
+/// [`FrameDiagnosis`] instead of bailing out on the first problem, so a
+/// caller can see the detected `df`, the length/CRC checks that passed or
+/// failed, and a human-readable reason in one call -- the tool to reach
+/// for when a receiver is producing frames the client silently discards
+/// rather than decoding.
+pub fn diagnose(data: &[u8]) -> FrameDiagnosis {
+    let actual_len = data.len();
+
+    if actual_len == 2 {
+        return FrameDiagnosis {
+            df: Some(DF_MODEAC),
+            expected_len: Some(2),
+            actual_len,
+            df_supported: true,
+            crc_syndrome: None,
+            reason: "Mode-A/C reply; always accepted".to_string(),
+        };
+    }
+
+    let Some(&first_byte) = data.first() else {
+        return FrameDiagnosis {
+            df: None,
+            expected_len: None,
+            actual_len,
+            df_supported: false,
+            crc_syndrome: None,
+            reason: "empty payload; no Downlink Format byte to read".to_string(),
+        };
+    };
+
+    let df = ((first_byte >> 3) & 31) as u32;
+    let expected_len = if df < 16 { 7 } else { 14 };
+    let df_supported = matches!(df, 0 | 4 | 5 | 11 | 16 | 17 | 18 | 19 | 20 | 21);
+
+    if actual_len != expected_len {
+        return FrameDiagnosis {
+            df: Some(df),
+            expected_len: Some(expected_len),
+            actual_len,
+            df_supported,
+            crc_syndrome: None,
+            reason: format!("DF{} expects a {}-byte payload, got {}", df, expected_len, actual_len),
+        };
+    }
+
+    if !df_supported {
+        return FrameDiagnosis {
+            df: Some(df),
+            expected_len: Some(expected_len),
+            actual_len,
+            df_supported,
+            crc_syndrome: None,
+            reason: format!("DF{} is not a Downlink Format this decoder handles", df),
+        };
+    }
+
+    let bits = if actual_len * 8 >= modes_crc::LONG_MSG_BITS as usize {
+        modes_crc::LONG_MSG_BITS
+    } else {
+        modes_crc::SHORT_MSG_BITS
+    };
+    let crc_syndrome = modes_crc::crc_residual(data, Some(bits));
+
+    let reason = match decode_message(0, 0, data) {
+        Ok(_) => "frame decodes cleanly".to_string(),
+        Err(err) => err.to_string(),
+    };
+
+    FrameDiagnosis { df: Some(df), expected_len: Some(expected_len), actual_len, df_supported, crc_syndrome: Some(crc_syndrome), reason }
+}
+
+/// True if `message`'s altitude subfield is present (its raw bits are
+/// non-zero, ruling out the ordinary "altitude not available" encoding)
+/// but [`decode_ac13`]/[`decode_ac12`] still couldn't decode it (an
+/// unsupported metric encoding or an illegal Gillham code). Mirrors the
+/// same bit extraction `decode()` uses for each Downlink Format, so it
+/// only ever disagrees with `decode()` about whether to surface an error,
+/// never about the decoded altitude itself.
+fn has_invalid_altitude_encoding(message: &ModesMessage) -> bool {
+    match message.df {
+        0 | 4 | 16 | 20 => {
+            let raw = ((message.data[2] & 0x1f) as u32) << 8 | (message.data[3] as u32);
+            raw != 0 && decode_ac13(raw).is_none()
+        }
+        17 | 18 => {
+            let metype = message.data[4] >> 3;
+            if (9..=18).contains(&metype) || (20..22).contains(&metype) {
+                let raw = ((message.data[5] << 4) | ((message.data[6] & 0xF0) >> 4)) as u32;
+                raw != 0 && decode_ac12(raw).is_none()
+            } else {
+                false
+            }
+        }
+        _ => false,
+    }
+}
+
+// internal entry point to build a new event message
+// steals a reference from eventdata
+pub fn modesmessage_new_eventmessage(msgtype: u32, timestamp: u64, eventdata: BTreeMap<String, EventData>) -> ModesMessage {
+    let mut message: ModesMessage = ModesMessage::default();
+
+    message.df = msgtype;
+    message.timestamp = timestamp;
+    message.eventdata = eventdata;
+
+    message
+}
+
+/// Builds a `DF_EVENT_TIMESTAMP_JUMP` event: the receiver's clock moved
+/// by more than expected between two consecutive frames. Carries the
+/// previous timestamp and the signed delta (in clock ticks) so a
+/// consumer can decide whether the jump is within tolerance.
+pub fn new_timestamp_jump_event(timestamp: u64, previous_timestamp: u64) -> ModesMessage {
+    let mut eventdata = BTreeMap::new();
+    eventdata.insert("previous_timestamp".to_string(), EventData::Int(previous_timestamp as i64));
+    eventdata.insert(
+        "delta".to_string(),
+        EventData::Int(timestamp as i64 - previous_timestamp as i64),
+    );
+    modesmessage_new_eventmessage(DF_EVENT_TIMESTAMP_JUMP, timestamp, eventdata)
+}
+
+/// Builds a `DF_EVENT_EPOCH_ROLLOVER` event: the receiver's 48-bit clock
+/// wrapped back around to (near) zero, so timestamp arithmetic spanning
+/// this event needs to account for the epoch boundary. `rollover_count`
+/// is the number of times this has now happened since tracking started,
+/// so a consumer can reconstruct a monotonic clock by adding
+/// `rollover_count * TIMESTAMP_EPOCH_TICKS` to raw timestamps seen after
+/// this event. `timestamp` is the (small) post-rollover value.
+pub fn new_epoch_rollover_event(timestamp: u64, rollover_count: u64) -> ModesMessage {
+    let mut eventdata = BTreeMap::new();
+    eventdata.insert("rollover_count".to_string(), EventData::Int(rollover_count as i64));
+    modesmessage_new_eventmessage(DF_EVENT_EPOCH_ROLLOVER, timestamp, eventdata)
+}
+
+// The 12MHz counter is a 48-bit value (see `libbeast::TimestampFormat::Mhz12`);
+// this is how far from either end of its range a jump is treated as an
+// ordinary epoch rollover rather than a clock discontinuity.
+pub const TIMESTAMP_EPOCH_TICKS: u64 = 1 << 48;
+
+/// GPS-synchronized Radarcape timestamps (`libbeast::TimestampFormat::GpsNanos`)
+/// don't wrap a 48-bit counter like `TIMESTAMP_EPOCH_TICKS`: the top 18
+/// bits are whole seconds since UTC midnight (0..86399) and the low 30 are
+/// nanoseconds within that second, so the raw value instead resets to 0
+/// every UTC midnight. Pass this as the modulus to
+/// `TimestampJumpDetector::with_threshold_and_modulus` when tracking a
+/// `GpsNanos` stream, so a midnight reset is recognized as a rollover
+/// rather than a multi-day clock discontinuity.
+pub const GPS_NANOS_EPOCH_TICKS: u64 = 86_400 << 30;
+
+/// A jump larger than this many ticks (1 second at 12MHz) between two
+/// consecutive frames, not explained by an epoch rollover, is reported as
+/// a `DF_EVENT_TIMESTAMP_JUMP` event.
+pub const DEFAULT_TIMESTAMP_JUMP_THRESHOLD: u64 = 12_000_000;
+
+/// Tracks the timestamp of the last frame seen and reports
+/// `DF_EVENT_TIMESTAMP_JUMP`/`DF_EVENT_EPOCH_ROLLOVER` events as
+/// consecutive timestamps warrant: a jump of more than `threshold` ticks
+/// that lands near zero after starting near the top of `modulus`'s range
+/// is a rollover, any other jump bigger than `threshold` is a
+/// discontinuity, and anything else is ordinary elapsed time. `modulus`
+/// defaults to `TIMESTAMP_EPOCH_TICKS` (the 12MHz counter's 48-bit
+/// range); pass `GPS_NANOS_EPOCH_TICKS` via `with_threshold_and_modulus`
+/// when tracking a GPS-synchronized Radarcape instead.
+pub struct TimestampJumpDetector {
+    threshold: u64,
+    modulus: u64,
+    previous: Option<u64>,
+    rollover_count: u64,
+}
+
+impl TimestampJumpDetector {
+    pub fn new() -> Self {
+        TimestampJumpDetector::with_threshold(DEFAULT_TIMESTAMP_JUMP_THRESHOLD)
+    }
+
+    pub fn with_threshold(threshold: u64) -> Self {
+        TimestampJumpDetector::with_threshold_and_modulus(threshold, TIMESTAMP_EPOCH_TICKS)
+    }
+
+    /// Like `with_threshold`, but also overrides the counter modulus used
+    /// to recognize a rollover -- use `GPS_NANOS_EPOCH_TICKS` for a
+    /// `libbeast::TimestampFormat::GpsNanos` receiver, whose raw
+    /// timestamp resets at UTC midnight rather than wrapping a 48-bit
+    /// counter.
+    pub fn with_threshold_and_modulus(threshold: u64, modulus: u64) -> Self {
+        TimestampJumpDetector { threshold, modulus, previous: None, rollover_count: 0 }
+    }
+
+    /// Records `timestamp` as the most recently seen frame timestamp, and
+    /// returns a `DF_EVENT_EPOCH_ROLLOVER` event if it looks like
+    /// `modulus`'s counter wrapping around, a `DF_EVENT_TIMESTAMP_JUMP`
+    /// event if it's some other jump too big to be ordinary elapsed time,
+    /// or `None` otherwise.
+    pub fn update(&mut self, timestamp: u64) -> Option<ModesMessage> {
+        let previous = self.previous.replace(timestamp)?;
+
+        let is_rollover = previous > self.modulus - self.threshold && timestamp < self.threshold;
+        if is_rollover {
+            self.rollover_count += 1;
+            return Some(new_epoch_rollover_event(timestamp, self.rollover_count));
+        }
+
+        let delta = (timestamp as i128) - (previous as i128);
+        if delta.unsigned_abs() > self.threshold as u128 {
+            Some(new_timestamp_jump_event(timestamp, previous))
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for TimestampJumpDetector {
+    fn default() -> Self {
+        TimestampJumpDetector::new()
+    }
+}
+
+/// Forward ticks from `previous` to `current` on the 48-bit 12MHz clock,
+/// correctly accounting for a wrap around `TIMESTAMP_EPOCH_TICKS` (unlike
+/// a plain `current - previous`, which would underflow/produce a huge
+/// value across a rollover).
+fn ticks_since(current: u64, previous: u64) -> u64 {
+    current.wrapping_sub(previous) & (TIMESTAMP_EPOCH_TICKS - 1)
+}
+
+/// The 12MHz Beast/Radarcape tick rate, in ticks per second. See
+/// `TIMESTAMP_EPOCH_TICKS`.
+#[cfg(feature = "std")]
+pub const TICKS_PER_SECOND: f64 = 12_000_000.0;
+
+/// Anchors the 12MHz tick counter to wall-clock time, so raw timestamps
+/// can be turned into a `SystemTime` for logging: a receive loop captures
+/// `SystemTime::now()` and the tick count of its first frame, then feeds
+/// every later frame's tick count through [`ClockRef::ticks_to_systemtime`].
+/// Ticks are always taken as forward elapsed time from `reference_ticks`
+/// via [`ticks_since`], so this handles the 48-bit counter wrapping around
+/// the same way `TimestampJumpDetector` does.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct ClockRef {
+    reference_time: std::time::SystemTime,
+    reference_ticks: u64,
+}
+
+#[cfg(feature = "std")]
+impl ClockRef {
+    pub fn new(reference_time: std::time::SystemTime, reference_ticks: u64) -> Self {
+        ClockRef { reference_time, reference_ticks }
+    }
+
+    /// Maps a raw tick count to the `SystemTime` it corresponds to,
+    /// assuming `ticks` is at or after `reference_ticks` (accounting for
+    /// any number of 48-bit rollovers in between).
+    pub fn ticks_to_systemtime(&self, ticks: u64) -> std::time::SystemTime {
+        let elapsed_ticks = ticks_since(ticks, self.reference_ticks);
+        let elapsed = std::time::Duration::from_secs_f64(elapsed_ticks as f64 / TICKS_PER_SECOND);
+        self.reference_time + elapsed
+    }
+}
+
+/// A ~100ms window (in 12MHz ticks) is the default for `DedupWindow`: long
+/// enough to cover the same transmission being decoded more than once
+/// (e.g. by more than one receiver feeding this client), short enough not
+/// to confuse a later, genuinely new transmission with the same payload.
+pub const DEFAULT_DEDUP_WINDOW_TICKS: u64 = 1_200_000;
+
+/// De-duplicates messages carrying the same payload (see
+/// [`ModesMessage::content_key`]) seen within a sliding window of the
+/// receiver's 12MHz timestamp, so the same physical transmission decoded
+/// more than once isn't forwarded twice.
+pub struct DedupWindow {
+    window: u64,
+    seen: BTreeMap<Vec<u8>, u64>,
+}
+
+impl DedupWindow {
+    pub fn new() -> Self {
+        DedupWindow::with_window(DEFAULT_DEDUP_WINDOW_TICKS)
+    }
+
+    pub fn with_window(window: u64) -> Self {
+        DedupWindow { window, seen: BTreeMap::new() }
+    }
+
+    /// Records `msg` as seen at its timestamp, evicting entries that have
+    /// aged out of the window first.
+    ///
+    /// # Returns
+    ///
+    /// `true` if this payload was not seen within the window (i.e. `msg`
+    /// should be forwarded), or `false` if it's a duplicate of something
+    /// already seen within the window (i.e. `msg` should be dropped).
+    pub fn observe(&mut self, msg: &ModesMessage) -> bool {
+        self.evict_older_than(msg.timestamp);
+
+        let key = msg.content_key().to_vec();
+        let is_duplicate = self.seen.contains_key(&key);
+        self.seen.insert(key, msg.timestamp);
+        !is_duplicate
+    }
+
+    fn evict_older_than(&mut self, now: u64) {
+        let window = self.window;
+        self.seen.retain(|_, &mut seen_at| ticks_since(now, seen_at) < window);
+    }
+}
+
+impl Default for DedupWindow {
+    fn default() -> Self {
+        DedupWindow::new()
+    }
+}
+
+/// Accumulates dump1090-style decode counters over some interval (a
+/// per-connection total, a periodic reporting window, whatever the caller
+/// resets it on) so an operator can see message volume and CRC health at
+/// a glance, without instrumenting every call site by hand.
+///
+/// Only the AVR input path currently feeds a `Stats`: the Beast path only
+/// decodes as far as `libbeast::Frame`, not a `ModesMessage`, so it has no
+/// `valid`/`corrected`/`df` to record.
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    total_frames: u64,
+    valid_frames: u64,
+    crc_failures: u64,
+    corrected_frames: u64,
+    frames_by_df: BTreeMap<u32, u64>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Stats::default()
+    }
+
+    /// Records one decoded `msg`: every message counts toward
+    /// `total_frames` and, if valid, `frames_by_df`; an invalid message
+    /// counts as a CRC failure, and a valid one reached via
+    /// `ModesMessage::corrected` counts as a corrected frame.
+    pub fn record(&mut self, msg: &ModesMessage) {
+        self.total_frames += 1;
+        if msg.valid {
+            self.valid_frames += 1;
+            *self.frames_by_df.entry(msg.df).or_insert(0) += 1;
+            if msg.corrected {
+                self.corrected_frames += 1;
+            }
+        } else {
+            self.crc_failures += 1;
+        }
+    }
+
+    pub fn total_frames(&self) -> u64 {
+        self.total_frames
+    }
+
+    pub fn valid_frames(&self) -> u64 {
+        self.valid_frames
+    }
+
+    pub fn crc_failures(&self) -> u64 {
+        self.crc_failures
+    }
+
+    pub fn corrected_frames(&self) -> u64 {
+        self.corrected_frames
+    }
+
+    pub fn frames_for_df(&self, df: u32) -> u64 {
+        self.frames_by_df.get(&df).copied().unwrap_or(0)
+    }
+
+    /// Resets every counter to zero, ready for the next reporting window.
+    pub fn reset(&mut self) {
+        *self = Stats::new();
+    }
+}
+
+impl fmt::Display for Stats {
+    /// A dump1090-style one-line summary: totals, then a per-DF breakdown
+    /// in ascending DF order.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} frames ({} valid, {} CRC failures, {} corrected)",
+            self.total_frames, self.valid_frames, self.crc_failures, self.corrected_frames
+        )?;
+        for (df, count) in &self.frames_by_df {
+            write!(f, " DF{}={}", df, count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Default window (in 12MHz ticks) that [`Quality`] averages its rates
+/// over: 10 seconds is short enough to reflect a reception problem quickly,
+/// long enough that a single quiet or bursty second doesn't swing the
+/// numbers wildly.
+#[cfg(feature = "std")]
+pub const DEFAULT_QUALITY_WINDOW_TICKS: u64 = 120_000_000;
+
+#[cfg(feature = "std")]
+#[derive(Clone, Copy)]
+struct QualitySample {
+    timestamp: u64,
+    valid: bool,
+    has_position: bool,
+}
+
+/// One point-in-time read of [`Quality`]'s rolling window.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct QualitySnapshot {
+    pub message_rate: f64,
+    pub position_rate: f64,
+    pub valid_fraction: f64,
+}
+
+/// Tracks reception quality over a rolling window of the receiver's 12MHz
+/// timestamp: messages/sec, positions/sec, and the fraction of messages in
+/// the window that passed CRC -- the same at-a-glance numbers mlat-server
+/// shows its operators for each connected receiver. Unlike [`Stats`], which
+/// accumulates until explicitly `reset()`, a `Quality`'s numbers always
+/// describe the last `window`-worth of traffic, so they reflect what's
+/// arriving right now rather than since start-up.
+#[cfg(feature = "std")]
+pub struct Quality {
+    window: u64,
+    samples: std::collections::VecDeque<QualitySample>,
+    valid_count: u64,
+    position_count: u64,
+}
+
+#[cfg(feature = "std")]
+impl Quality {
+    pub fn new() -> Self {
+        Quality::with_window(DEFAULT_QUALITY_WINDOW_TICKS)
+    }
+
+    pub fn with_window(window: u64) -> Self {
+        Quality { window, samples: std::collections::VecDeque::new(), valid_count: 0, position_count: 0 }
+    }
+
+    /// Records one decoded `msg`, evicting samples that have aged out of
+    /// the window first. A message counts toward `position_rate` if it
+    /// carries a CPR field at all (`even_cpr`/`odd_cpr`) -- whether that CPR
+    /// pair actually resolves to a fix is `CprDecoder`'s job, not this
+    /// counter's, so this is an upper bound on the real position rate.
+    pub fn record(&mut self, msg: &ModesMessage) {
+        self.evict_older_than(msg.timestamp);
+
+        let has_position = msg.valid && (msg.even_cpr || msg.odd_cpr);
+        if msg.valid {
+            self.valid_count += 1;
+        }
+        if has_position {
+            self.position_count += 1;
+        }
+        self.samples.push_back(QualitySample { timestamp: msg.timestamp, valid: msg.valid, has_position });
+    }
+
+    fn evict_older_than(&mut self, now: u64) {
+        while let Some(sample) = self.samples.front() {
+            if ticks_since(now, sample.timestamp) < self.window {
+                break;
+            }
+            let sample = self.samples.pop_front().unwrap();
+            if sample.valid {
+                self.valid_count -= 1;
+            }
+            if sample.has_position {
+                self.position_count -= 1;
+            }
+        }
+    }
+
+    /// The window's current numbers, or all-zero if nothing has been
+    /// recorded yet (or everything recorded has aged out), rather than
+    /// dividing by zero for an idle receiver. With only a handful of
+    /// samples the rates are necessarily noisy; they settle down once the
+    /// window fills up.
+    pub fn snapshot(&self) -> QualitySnapshot {
+        let (Some(oldest), Some(newest)) = (self.samples.front(), self.samples.back()) else {
+            return QualitySnapshot::default();
+        };
+
+        let span_secs = (ticks_since(newest.timestamp, oldest.timestamp).max(1)) as f64 / TICKS_PER_SECOND;
+        let count = self.samples.len() as f64;
+
+        QualitySnapshot {
+            message_rate: count / span_secs,
+            position_rate: self.position_count as f64 / span_secs,
+            valid_fraction: self.valid_count as f64 / count,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for Quality {
+    fn default() -> Self {
+        Quality::new()
+    }
+}
+
+// A structure representing a modes message.
+//
+// `track` is an `f64`, which has no total order or exact-hash semantics,
+// so this only derives `PartialEq`; nothing in this codebase puts a
+// `ModesMessage` in a `HashSet`/`HashMap` or relies on `Eq` (the inherent
+// `hash`/`compare` methods below predate this struct and cover that use
+// case over the raw message bytes instead).
+//
+// `Serialize`/`Deserialize` are behind the `serde` feature (off by
+// default) so `no_std` and other minimal builds aren't forced to pull in
+// serde; `data` is hex-encoded rather than emitted as a byte array, via
+// `hex_data`, so JSON lines logged from `--output` stay readable.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModesMessage {
+    pub timestamp: u64,                        // 12MHz timestamp
+    pub signal: u8,                            // signal level
+
+    pub df: u32,                               // downlink format or a special DF_* value
+    pub nuc: u32,                              // Navigation uncertainty category; NUCp value
+
+    pub even_cpr: bool,                        // CPR even-format flag
+    pub odd_cpr: bool,                         // CPR odd-format flag
+    pub cpr_lat: u32,                          // raw 17-bit encoded CPR latitude
+    pub cpr_lon: u32,                          // raw 17-bit encoded CPR longitude
+    pub surface: bool,                         // Is cpr_lat/cpr_lon a surface (metype 5-8) position rather than airborne?
+    pub valid: bool,                           // Does the message look OK?
+    pub corrected: bool,                       // Was valid reached by fixing a CRC bit error, rather than a clean residual?
+    pub crc: u32,                              // Cyclic redundancy check value
+    pub address: i32,                          // ICAO address
+    pub altitude: i32,                         // altitude information
+    pub callsign: Option<String>,              // flight ID, from an identification message
+    pub ground_speed: Option<u16>,             // ground speed in knots, from a velocity or surface movement message
+    pub track: Option<f64>,                    // ground track in degrees (0..360), from a velocity or surface movement message
+    pub vertical_rate: Option<i32>,            // vertical rate in feet per minute, from a velocity message
+    pub squawk: Option<u16>,                   // 4-digit octal identity code, from a DF5/DF21 surveillance reply
+    pub nac_p: Option<u8>,                     // Navigation Accuracy Category for position, from an operational status message (metype 31)
+    pub sil: Option<u8>,                       // Source Integrity Level, from an operational status message (metype 31)
+    pub adsb_version: Option<u8>,              // ADS-B version number (0, 1 or 2), from an operational status message (metype 31)
+    pub is_tisb: bool,                         // DF18 only: CF says this is a TIS-B relay, not a self-reported position
+    pub is_adsr: bool,                         // DF18 only: CF says this is an ADS-R rebroadcast, not a self-reported position
+    pub on_ground: Option<bool>,               // VS (vertical status) field, from a DF0/DF16 short/long air-air surveillance reply
+    pub sensitivity_level: Option<u8>,         // SL (ACAS sensitivity level) field, from a DF0/DF16 short/long air-air surveillance reply
+    pub reply_information: Option<u8>,         // RI (reply information / airspeed capability) field, from a DF0/DF16 short/long air-air surveillance reply
+    pub interrogator: Option<u8>,              // II/SI interrogator code, from a DF11 all-call reply with a nonzero code
+
+    #[cfg_attr(feature = "serde", serde(with = "hex_data"))]
+    pub data: Vec<u8>,                         // The payload data
+    pub datalen: usize,                        // Length of the payload data
+
+    pub max_correctable_bits: u8,               // cap on CRC bit errors decode() will try to fix
+    pub trust_crc: bool,                        // skip CRC validity checks on DF11/17/18/19, trusting length alone
+
+    eventdata: BTreeMap<String, EventData>,     // event data dictionary for special event messages
+}
+
+impl ModesMessage {
+    fn new(
+        timestamp: u64,
+        signal: u8,
+        df: u32,
+        nuc: u32,
+        even_cpr: bool,
+        odd_cpr: bool,
+        valid: bool,
+        crc: u32,
+        address: i32,
+        altitude: i32,
+        data: Vec<u8>,
+        datalen: usize,
+        eventdata: BTreeMap<String, EventData>,
+    ) -> Self {
+        ModesMessage {
+            timestamp,
+            signal,
+            df,
+            nuc,
+            even_cpr,
+            odd_cpr,
+            cpr_lat: 0,
+            cpr_lon: 0,
+            surface: false,
+            valid,
+            corrected: false,
+            crc,
+            address,
+            altitude,
+            callsign: None,
+            ground_speed: None,
+            track: None,
+            vertical_rate: None,
+            squawk: None,
+            nac_p: None,
+            sil: None,
+            adsb_version: None,
+            is_tisb: false,
+            is_adsr: false,
+            on_ground: None,
+            sensitivity_level: None,
+            reply_information: None,
+            interrogator: None,
+            data,
+            datalen,
+            max_correctable_bits: modes_crc::DEFAULT_MAX_CORRECTABLE_BITS,
+            trust_crc: false,
+            eventdata,
+        }
+    }
+
+    pub(crate) fn default() -> Self {
+        // minimal init
+        ModesMessage {
+            timestamp: 0,
+            signal: 0,
+            df: 0,
+            nuc: 0,
+            even_cpr: false,
+            odd_cpr: false,
+            cpr_lat: 0,
+            cpr_lon: 0,
+            surface: false,
+            valid: false,
+            corrected: false,
+            crc: 0,
+            address: 0,
+            altitude: 0,
+            callsign: None,
+            ground_speed: None,
+            track: None,
+            vertical_rate: None,
+            squawk: None,
+            nac_p: None,
+            sil: None,
+            adsb_version: None,
+            is_tisb: false,
+            is_adsr: false,
+            on_ground: None,
+            sensitivity_level: None,
+            reply_information: None,
+            interrogator: None,
+            data: Vec::new(),
+            datalen: 0,
+            max_correctable_bits: modes_crc::DEFAULT_MAX_CORRECTABLE_BITS,
+            trust_crc: false,
+            eventdata: BTreeMap::new(),
+        }
+    }
+
+    // Function to build a new message from a buffer.
+    pub fn from_buffer(timestamp: u64, signal: u8, data: Vec<u8>) -> Result<Self, DecodeError> {
+        let datalen = data.len();
+        if datalen != 2 && datalen != 7 && datalen != 14 {
+            return Err(DecodeError::WrongLength { len: datalen });
+        }
+
+        let mut message = ModesMessage::default();
+        message.timestamp = timestamp;
+        message.signal = signal;
+        message.data = data;
+        message.datalen = datalen;
+        message.decode()?;
+
+        Ok(message)
+    }
+
+    /// Like [`ModesMessage::from_buffer`], but skips the CRC validity
+    /// checks on DF11/17/18/19 (see `trust_crc`) and marks those messages
+    /// valid based on length alone, rather than rejecting or bit-
+    /// correcting an uncorrectable residual. For a trusted local pipeline
+    /// (e.g. a dump1090 that already validated CRC before relaying) this
+    /// avoids redoing CRC work that only costs cycles; on an untrusted or
+    /// noisy feed it will let corrupted frames through, so callers should
+    /// only reach for this in that trusted-source case.
+    pub fn from_buffer_trusted(timestamp: u64, signal: u8, data: Vec<u8>) -> Result<Self, DecodeError> {
+        let datalen = data.len();
+        if datalen != 2 && datalen != 7 && datalen != 14 {
+            return Err(DecodeError::WrongLength { len: datalen });
+        }
+
+        let mut message = ModesMessage::default();
+        message.timestamp = timestamp;
+        message.signal = signal;
+        message.data = data;
+        message.datalen = datalen;
+        message.trust_crc = true;
+        message.decode()?;
+
+        Ok(message)
+    }
+
+    /// Builds a message from a hex-encoded payload string (e.g.
+    /// `"8d4840d6202cc371c32ce0576098a87"`), so decode fixtures can be
+    /// written as a readable literal instead of a split byte vector.
+    /// Panics if `hex` isn't a valid hex string; that's a malformed
+    /// fixture, not something a caller needs `Result` to handle.
+    #[cfg(any(test, feature = "testutils"))]
+    pub fn from_hex(timestamp: u64, signal: u8, hex: &str) -> Result<Self, DecodeError> {
+        let data = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("from_hex fixture is not valid hex"))
+            .collect();
+        ModesMessage::from_buffer(timestamp, signal, data)
+    }
+
+    // Function to build a new event message.
+    pub fn new_event_message(
+        event_type: u32,
+        timestamp: u64,
+        eventdata: BTreeMap<String, EventData>,
+    ) -> Self {
+        modesmessage_new_eventmessage(event_type, timestamp, eventdata)
+    }
+
+    /// The Navigation Integrity Category for this message. This decoder
+    /// doesn't decode a separate NIC subfield (it only recognizes ADS-B
+    /// version 0 traffic), so it's just `self.nuc` under another name;
+    /// see [`nucp_to_rc`].
+    pub fn nic(&self) -> u32 {
+        self.nuc
+    }
+
+    /// The ICAO address as a 6-character uppercase hex string, e.g.
+    /// `"4840D6"`. Masks off everything but the low 24 bits first, so an
+    /// address with the high bit set (which `address`, an `i32`, would
+    /// otherwise sign-extend) still formats correctly. Returns an empty
+    /// string if this message doesn't carry a decoded address: a Mode-A/C
+    /// reply has no ICAO address at all, and a message `decode()` couldn't
+    /// validate has nothing trustworthy to report.
+    pub fn icao_hex(&self) -> String {
+        if !self.valid || self.df == DF_MODEAC {
+            return String::new();
+        }
+        format!("{:06X}", self.address as u32 & 0x00FF_FFFF)
+    }
+
+    /// Whether this message's sender was on the ground, consolidating the
+    /// several different bits that can say so depending on `df` so
+    /// callers don't have to reimplement this per-DF: a surface-position
+    /// metype (5-8, `self.surface`) on DF17/18, the VS bit on DF0/16
+    /// (`self.on_ground`, already decoded), FS on DF4/5/20/21, and CA on
+    /// DF17. `None` when this message doesn't carry one of those bits, or
+    /// carries one whose value (e.g. FS 4/6, CA 6/7) doesn't distinguish
+    /// airborne from on the ground.
+    pub fn on_ground(&self) -> Option<bool> {
+        if !self.valid {
+            return None;
+        }
+        if self.surface {
+            return Some(true);
+        }
+        match self.df {
+            0 | 16 => self.on_ground,
+            4 | 5 | 20 | 21 => match (self.data[0] >> 3) & 0x07 {
+                0 | 2 => Some(false),
+                1 | 3 => Some(true),
+                _ => None,
+            },
+            17 => match self.data[0] & 0x07 {
+                4 => Some(true),
+                5 => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn decode(&mut self) -> Result<(), DecodeError> {
+        let mut crc: u32;
+
+        // clear state
+        self.valid = false;
+        self.corrected = false;
+        self.nuc = 0;
+        self.odd_cpr = false;
+        self.even_cpr = false;
+        self.cpr_lat = 0;
+        self.cpr_lon = 0;
+        self.surface = false;
+        self.crc = 0;
+        self.address = 0;
+        self.altitude = 0;
+        self.callsign = None;
+        self.ground_speed = None;
+        self.track = None;
+        self.vertical_rate = None;
+        self.squawk = None;
+        self.nac_p = None;
+        self.sil = None;
+        self.adsb_version = None;
+        self.is_tisb = false;
+        self.is_adsr = false;
+        self.on_ground = None;
+        self.sensitivity_level = None;
+        self.reply_information = None;
+
+        if self.datalen == 2 {
+            self.df = DF_MODEAC;
+            self.address = ((self.data[0] as i32) << 8) | self.data[1] as i32;
+            self.valid = true;
+            return Ok(());
+        }
+        self.df = ((self.data[0] >> 3) & 31) as u32;
+        if (self.df < 16 && self.datalen != 7) || (self.df >= 16 && self.datalen != 14) {
+            // wrong length, no further processing
+            return Err(DecodeError::WrongLength { len: self.datalen });
+        }
+        if self.df != 0 && self.df != 4 && self.df != 5 && self.df != 11 &&
+            self.df != 16 && self.df != 17 && self.df != 18 && self.df != 19 && self.df != 20 && self.df != 21 {
+            // we do not know how to handle this message type, no further processing
+            return Err(DecodeError::UnknownDf { df: self.df });
+        }
+        let bits = if self.datalen * 8 >= modes_crc::LONG_MSG_BITS as usize {
+            modes_crc::LONG_MSG_BITS
+        } else {
+            modes_crc::SHORT_MSG_BITS
+        };
+        crc = modes_crc::crc_residual(&self.data, Some(bits));
+        self.crc = crc;
+        match self.df {
+            0 | 4 | 16 | 20 => {
+                // Parity is xored with the sender's ICAO address; there
+                // is nothing to "correct" here, just an overlay to undo.
+                self.address = modes_crc::recover_address(&self.data, bits).unwrap_or(self.crc) as i32;
+                // AC13 of 0 (or an illegal Gillham code) is the standard
+                // "altitude not available" encoding on a surveillance
+                // reply; leave `self.altitude` at its reset sentinel of 0
+                // rather than unwrapping a `None`.
+                if let Some(altitude) = decode_ac13(((self.data[2] & 0x1f) as u32) << 8 | (self.data[3] as u32)) {
+                    self.altitude = altitude;
+                }
+                if self.df == 0 || self.df == 16 {
+                    let (on_ground, sensitivity_level, reply_information) = decode_short_air_air_status(&self.data);
+                    self.on_ground = Some(on_ground);
+                    self.sensitivity_level = Some(sensitivity_level);
+                    self.reply_information = Some(reply_information);
+                }
+                self.valid = true;
+            },
+            5 | 21 => {
+                self.address = modes_crc::recover_address(&self.data, bits).unwrap_or(self.crc) as i32;
+                self.valid = true;
+                self.squawk = Some(decode_identity(&self.data[2..4]));
+            },
+            24 => {
+                self.address = modes_crc::recover_address(&self.data, bits).unwrap_or(self.crc) as i32;
+                self.valid = true;
+            },
+            11 => {
+                // The residual is the II/SI interrogator code in its low 7
+                // bits; a frame with those bits already zero is clean.
+                // Otherwise, assume a zero IID and try to explain the
+                // residual as a correctable bit error; a residual that
+                // survives correction is treated as a (nonzero-IID) valid
+                // frame rather than an uncorrectable error, matching this
+                // decoder's original permissive DF11 check.
+                if self.trust_crc || self.crc == 0 {
+                    self.valid = true;
+                } else if modes_crc::correct_errors(&mut self.data, bits, self.max_correctable_bits).is_some() {
+                    self.crc = modes_crc::crc_residual(&self.data, Some(bits));
+                    self.valid = true;
+                    self.corrected = true;
+                } else {
+                    self.valid = self.crc & !0x7f == 0;
+                }
+                if self.valid {
+                    self.address = ((self.data[1] as u32) << 16 | (self.data[2] as u32) << 8 | self.data[3] as u32) as i32;
+                    self.interrogator = df11_interrogator(&self.data);
+                }
+            },
+            17 | 18 => {
+                if self.trust_crc || self.crc == 0 {
+                    self.valid = true;
+                } else if modes_crc::correct_errors(&mut self.data, bits, self.max_correctable_bits).is_some() {
+                    self.valid = true;
+                    self.corrected = true;
+                }
+                if self.valid {
+                    self.crc = 0;
+                    let mut metype: u8;
+                    self.address = ((self.data[1] as u32) << 16 | (self.data[2] as u32) << 8 | self.data[3] as u32) as i32;
+                    if self.df == 18 {
+                        // For DF17 this field is CA (capability); for DF18
+                        // it's CF (control field), which says whether the
+                        // address above is really the transmitter's own
+                        // ICAO address or a relayed/anonymous one. CF 2/3/4
+                        // are TIS-B (a ground station relaying a track it
+                        // observed by other means, e.g. radar); CF 5 is
+                        // TIS-B with a non-ICAO (anonymous) address; CF 6 is
+                        // ADS-R (a ground station rebroadcasting another
+                        // aircraft's ADS-B). Neither is a receiver reporting
+                        // its own position, so neither should be trusted as
+                        // one.
+                        match self.data[0] & 0x07 {
+                            2 | 3 | 4 | 5 => self.is_tisb = true,
+                            6 => self.is_adsr = true,
+                            _ => {},
+                        }
+                    }
+                    metype = self.data[4] >> 3;
+                    if metype >= 1 && metype <= 4 {
+                        self.callsign = decode_callsign(&self.data[4..11]);
+                    }
+                    if (5..=8).contains(&metype) {
+                        self.surface = true;
+                        self.nuc = 14 - metype as u32;
+                        let movement = ((self.data[4] & 0x07) << 4) | (self.data[5] >> 4);
+                        self.ground_speed = decode_surface_movement(movement).map(|s| s.round() as u16);
+                        if self.data[5] & 0x08 != 0 {
+                            let track_raw = ((self.data[5] & 0x07) << 4) | (self.data[6] >> 4);
+                            self.track = Some(track_raw as f64 * 360.0 / 128.0);
+                        }
+                        if self.data[6] & 0x04 != 0 {
+                            self.odd_cpr = true;
+                        } else {
+                            self.even_cpr = true;
+                        }
+                        self.cpr_lat = ((self.data[6] as u32 & 0x03) << 15) | ((self.data[7] as u32) << 7) | ((self.data[8] as u32) >> 1);
+                        self.cpr_lon = ((self.data[8] as u32 & 0x01) << 16) | ((self.data[9] as u32) << 8) | (self.data[10] as u32);
+                    }
+                    if metype == 19 {
+                        // Copied out to a local array, rather than passed
+                        // as a slice of `self.data`, so `apply_ground_velocity`
+                        // can take `&mut self` without overlapping the
+                        // borrow of `self.data` used to build the argument.
+                        let me: [u8; 7] = self.data[4..11].try_into().unwrap();
+                        self.vertical_rate = decode_vertical_rate(&me);
+                        self.apply_ground_velocity(&me);
+                    }
+                    if (metype >= 9 && metype <= 18) || (metype >= 20 && metype < 22) {
+                        if metype == 22 {
+                            self.nuc = 0;
+                        } else if metype <= 18 {
+                            self.nuc = 18 - metype as u32;
+                        } else {
+                            self.nuc = 29 - metype as u32;
+                        }
+                        if self.data[6] & 0x04 != 0 {
+                            self.odd_cpr = true;
+                        } else {
+                            self.even_cpr = true;
+                        }
+                        self.cpr_lat = ((self.data[6] as u32 & 0x03) << 15) | ((self.data[7] as u32) << 7) | ((self.data[8] as u32) >> 1);
+                        self.cpr_lon = ((self.data[8] as u32 & 0x01) << 16) | ((self.data[9] as u32) << 8) | (self.data[10] as u32);
+                        // AC12 of 0 is the standard "altitude not available"
+                        // encoding on an airborne-position squitter; leave
+                        // `self.altitude` at its reset sentinel of 0 rather
+                        // than unwrapping a `None`.
+                        if let Some(altitude) = decode_ac12(((self.data[5] << 4) | ((self.data[6] & 0xF0) >> 4)) as u32) {
+                            self.altitude = altitude as i32;
+                        }
+                    }
+                    if metype == 31 {
+                        // Operational status (airborne, subtype 0, or
+                        // surface, subtype 1). The capability-class and
+                        // operational-mode subfields (data[5..9]) differ in
+                        // meaning between the two subtypes, but nothing here
+                        // uses them yet; the version/NACp/SIL subfields we
+                        // do care about sit at the same bit offsets
+                        // regardless of subtype, so no subtype-specific
+                        // branch is needed to reach them.
+                        let subtype = self.data[4] & 0x07;
+                        if subtype == 0 || subtype == 1 {
+                            self.adsb_version = Some((self.data[9] >> 5) & 0x07);
+                            self.nac_p = Some(self.data[9] & 0x0F);
+                            self.sil = Some((self.data[10] >> 4) & 0x03);
+                        }
+                    }
+                }
+            },
+            19 => {
+                // Extended squitter, military application. Structurally
+                // the same envelope as DF17/18 -- a bare (non-address-
+                // overlaid) CRC over the whole frame, ICAO/military
+                // address in the next three bytes -- but the ME field
+                // beyond that is defined by the applicable military
+                // standard rather than ICAO Annex 10, so unlike DF17/18
+                // this decoder doesn't attempt to interpret it; callers
+                // that need the payload get it via `self.data` and decode
+                // it themselves.
+                if self.trust_crc || self.crc == 0 {
+                    self.valid = true;
+                } else if modes_crc::correct_errors(&mut self.data, bits, self.max_correctable_bits).is_some() {
+                    self.valid = true;
+                    self.corrected = true;
+                }
+                if self.valid {
+                    self.crc = 0;
+                    self.address = ((self.data[1] as u32) << 16 | (self.data[2] as u32) << 8 | self.data[3] as u32) as i32;
+                }
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    fn apply_ground_velocity(&mut self, me: &[u8]) {
+        if let Some((speed, track)) = decode_ground_velocity(me) {
+            self.ground_speed = Some(speed);
+            self.track = Some(track);
+        }
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn apply_ground_velocity(&mut self, _me: &[u8]) {}
+
+    /// Returns the length of the data in the message.
+    fn len(&self) -> usize {
+        self.datalen
+    }
+
+    /// Calculates a hash for the message using a simple hashing algorithm.
+    fn hash(&self) -> u32 {
+        let mut hash: u32 = 0;
+
+        // Jenkins one-at-a-time hash
+        for i in 0..4.min(self.datalen as usize) {
+            hash += self.data[i] as u32;
+            hash = hash.wrapping_add(hash << 10);
+            hash ^= hash >> 6;
+        }
+
+        hash = hash.wrapping_add(hash << 3);
+        hash ^= hash >> 11;
+        hash = hash.wrapping_add(hash << 15);
+
+        hash as u32
+    }
+
+    /// Compares two `ModesMessage` instances.
+    fn compare(&self, other: &Self) -> Ordering {
+        if self.datalen != other.datalen {
+            return self.datalen.cmp(&other.datalen);
+        }
+        self.data.as_slice().cmp(&other.data.as_slice())
+    }
+
+    /// A `Hash + Eq` key covering only the message payload, for
+    /// de-duplicating repeat receptions of the same physical frame (e.g.
+    /// heard by more than one receiver, or retransmitted) within a
+    /// de-dup window.
+    ///
+    /// `ModesMessage` does not derive `Hash`/`Eq` itself: `timestamp` and
+    /// `signal` differ on every reception even of the same frame, so a
+    /// field-wise hash would never consider two receptions equal, which
+    /// defeats the purpose of a `HashSet` here. This is unrelated to the
+    /// private `hash()`/`compare()` above: `hash()` only mixes in the
+    /// first 4 payload bytes (good enough for a fast hashtable bucket
+    /// index, ported from the upstream Python client's use of it, but not
+    /// collision-free), and `compare()` returns an `Ordering` for sorted
+    /// structures rather than a `HashSet`/`HashMap` key.
+    pub fn content_key(&self) -> &[u8] {
+        &self.data[..self.datalen]
+    }
+
+    /// Serializes this message as an AVR text line: `@<12-hex-digit
+    /// timestamp><hex payload>;` when `timestamp` is non-zero, or
+    /// `*<hex payload>;` otherwise -- the `*`/`@`-prefixed line format
+    /// dump1090's `--raw` output emits and `avr::parse_avr_line` parses.
+    /// Round-trips through that parser for any message built from a real
+    /// payload.
+    pub fn to_avr(&self) -> String {
+        let payload: String = self.data[..self.datalen].iter().map(|b| format!("{:02X}", b)).collect();
+        if self.timestamp != 0 {
+            format!("@{:012X}{};", self.timestamp, payload)
+        } else {
+            format!("*{};", payload)
+        }
+    }
+}
+
+// `Ord`/`PartialOrd` delegate to `compare()` (length, then raw bytes),
+// the same content-only comparison `hash()`/`content_key()` use -- not
+// the derived, full-field `PartialEq` above, which also considers
+// `timestamp`/`signal` and so disagrees with this ordering about which
+// messages are "equal" (see `content_key`'s doc comment for why that
+// split exists). This lets a `BTreeSet`/`BTreeMap` key or dedup on
+// message content alone, e.g. to order or range-query messages
+// regardless of when/where they were received.
+impl PartialOrd for ModesMessage {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Required by `Ord`; equality here is "compares as `Ordering::Equal`"
+// (same length and bytes), not the derived `PartialEq` above.
+impl Eq for ModesMessage {}
+
+impl Ord for ModesMessage {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.compare(other)
+    }
+}
+
+impl fmt::Display for ModesMessage {
+    /// Formats the `ModesMessage` for display: for a valid Mode-S frame,
+    /// a dump1090-style one-line summary of the fields `decode()` was
+    /// able to populate; otherwise the raw hex, or the event map for a
+    /// `DF_EVENT_*` pseudo-message.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.data.is_empty() {
+            if !self.valid {
+                let hex_data: String = self.data.iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect();
+                return write!(f, "{}", hex_data);
+            }
+
+            write!(f, "DF{} {:06X}", self.df, self.address)?;
+            if self.altitude != 0 {
+                write!(f, " alt={}ft", self.altitude)?;
+            }
+            if let Some(ref callsign) = self.callsign {
+                write!(f, " ident={}", callsign)?;
+            }
+            if let Some(speed) = self.ground_speed {
+                write!(f, " spd={}kt", speed)?;
+            }
+            if let Some(track) = self.track {
+                write!(f, " trk={:.0}", track)?;
+            }
+            if let Some(rate) = self.vertical_rate {
+                write!(f, " vrate={}fpm", rate)?;
+            }
+            if let Some(squawk) = self.squawk {
+                write!(f, " squawk={:04}", squawk)?;
+            }
+            Ok(())
+        } else {
+            if let Some(event_name) = df_event_name(self.df) {
+                write!(f, "{}@{}:{:?}", event_name, self.timestamp, self.eventdata)
+            } else {
+                write!(f, "DF{}@{}:{:?}", self.df, self.timestamp, self.eventdata)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modes_crc::{checksum, crc_residual, LONG_MSG_BITS, SHORT_MSG_BITS};
+
+    // DF17 airborne position, CA=5, ICAO 0xABCDEF, metype 11 (barometric
+    // altitude), even CPR, with the CRC field filled in so the frame is
+    // clean.
+    fn clean_df17_frame() -> [u8; 14] {
+        let mut data = [0u8; 14];
+        data[0] = (17 << 3) | 5; // DF17, CA=5
+        data[1] = 0xab;
+        data[2] = 0xcd;
+        data[3] = 0xef;
+        data[4] = 11 << 3; // metype 11, no status bits set
+        data[5] = 0x00;
+        data[6] = 0x00; // even CPR (bit 0x04 clear)
+        data[7] = 0x12;
+        data[8] = 0x34;
+        data[9] = 0x56;
+        data[10] = 0x78;
+
+        let crc = checksum(&data, Some(LONG_MSG_BITS));
+        data[11] = (crc >> 16) as u8;
+        data[12] = (crc >> 8) as u8;
+        data[13] = crc as u8;
+        data
+    }
+
+    #[test]
+    fn decode_validates_a_clean_df17_frame_and_populates_cpr_fields() {
+        let mut msg = ModesMessage::default();
+        msg.data = clean_df17_frame().to_vec();
+        msg.datalen = msg.data.len();
+
+        msg.decode().expect("clean frame decodes");
+
+        assert_eq!(msg.df, 17);
+        assert!(msg.valid);
+        assert_eq!(msg.crc, 0);
+        assert_eq!(msg.address, 0xabcdef);
+        assert_eq!(msg.nuc, 18 - 11);
+        assert!(msg.even_cpr);
+        assert!(!msg.odd_cpr);
+        assert_eq!(msg.cpr_lat, 0x91a);
+        assert_eq!(msg.cpr_lon, 0x5678);
+    }
+
+    #[test]
+    fn decode_marks_a_wrong_length_frame_invalid_without_indexing_past_it() {
+        let mut msg = ModesMessage::default();
+        msg.data = vec![0x8du8, 0xab, 0xcd]; // DF17 but way too short
+        msg.datalen = msg.data.len();
+
+        assert_eq!(msg.decode(), Err(DecodeError::WrongLength { len: 3 }));
+        assert!(!msg.valid);
+    }
+
+    #[test]
+    fn from_buffer_decodes_a_df17_frame_with_a_populated_altitude() {
+        let mut data = clean_df17_frame();
+        data[5] = 0x1f; // AC12 = 0x1f0, decodes to 5000ft
+        let crc = checksum(&data, Some(LONG_MSG_BITS));
+        data[11] = (crc >> 16) as u8;
+        data[12] = (crc >> 8) as u8;
+        data[13] = crc as u8;
+
+        let msg = ModesMessage::from_buffer(1234, 0x7f, data.to_vec()).expect("valid-length buffer decodes");
+
+        assert_eq!(msg.df, 17);
+        assert_eq!(msg.address, 0xabcdef);
+        assert_eq!(msg.altitude, 5000);
+        assert_eq!(msg.timestamp, 1234);
+        assert_eq!(msg.signal, 0x7f);
+    }
+
+    #[test]
+    fn from_hex_decodes_a_df17_frame_written_as_a_hex_literal() {
+        let hex: String = clean_df17_frame().iter().map(|b| format!("{:02x}", b)).collect();
+
+        let msg = ModesMessage::from_hex(1234, 0x7f, &hex).expect("valid hex payload decodes");
+
+        assert_eq!(msg.df, 17);
+        assert!(msg.valid);
+        assert_eq!(msg.address, 0xabcdef);
+    }
+
+    #[test]
+    fn from_buffer_rejects_a_buffer_with_an_unsupported_length() {
+        assert!(ModesMessage::from_buffer(0, 0, vec![0u8; 5]).is_err());
+    }
+
+    #[test]
+    fn decode_message_decodes_a_clean_df17_frame() {
+        let message = decode_message(1234, 0x7f, &clean_df17_frame()).expect("clean frame decodes");
+
+        assert_eq!(message.df, 17);
+        assert!(message.valid);
+        assert_eq!(message.address, 0xabcdef);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", feature = "parallel"))]
+    fn decode_batch_matches_decode_message_frame_by_frame() {
+        let good = clean_df17_frame();
+        let bad = vec![0x8du8, 0xab, 0xcd]; // wrong length
+        let frames = vec![
+            (1234u64, 0x7fu8, good.to_vec()),
+            (5678u64, 0x10u8, bad),
+        ];
+
+        let results = decode_batch(&frames);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().map(|m| m.address), Ok(0xabcdef));
+        assert_eq!(results[1], Err(DecodeError::WrongLength { len: 3 }));
+    }
+
+    #[test]
+    fn decode_message_rejects_an_unsupported_overall_length() {
+        assert_eq!(decode_message(0, 0, &[0u8; 5]), Err(DecodeError::WrongLength { len: 5 }));
+    }
+
+    #[test]
+    fn decode_message_rejects_a_length_that_does_not_match_its_downlink_format() {
+        let mut data = [0u8; 7];
+        data[0] = (17 << 3) | 5; // DF17 needs 14 bytes, not 7
+
+        assert_eq!(decode_message(0, 0, &data), Err(DecodeError::WrongLength { len: 7 }));
+    }
+
+    #[test]
+    fn decode_message_rejects_an_unhandled_downlink_format() {
+        let mut data = [0u8; 14];
+        data[0] = 22 << 3; // DF22, not in the handled set
+
+        assert_eq!(decode_message(0, 0, &data), Err(DecodeError::UnknownDf { df: 22 }));
+    }
+
+    #[test]
+    fn decode_message_extracts_the_address_from_a_clean_df19_frame() {
+        let mut data = [0u8; 14];
+        data[0] = (19 << 3) | 5;
+        data[1] = 0xab;
+        data[2] = 0xcd;
+        data[3] = 0xef;
+
+        let crc = checksum(&data, Some(LONG_MSG_BITS));
+        data[11] = (crc >> 16) as u8;
+        data[12] = (crc >> 8) as u8;
+        data[13] = crc as u8;
+
+        let message = decode_message(0, 0, &data).expect("clean DF19 frame should decode");
+        assert_eq!(message.df, 19);
+        assert_eq!(message.icao_hex(), "ABCDEF");
+        assert_eq!(message.altitude, 0);
+        assert_eq!(message.callsign, None);
+    }
+
+    #[test]
+    fn decode_message_rejects_a_frame_with_an_uncorrectable_crc() {
+        let mut data = clean_df17_frame();
+        data[11] ^= 0xff;
+        data[12] ^= 0xff;
+
+        assert_eq!(decode_message(0, 0, &data), Err(DecodeError::CrcFailed));
+    }
+
+    #[test]
+    fn from_buffer_trusted_accepts_a_df17_frame_with_an_uncorrectable_crc() {
+        let mut data = clean_df17_frame();
+        data[11] ^= 0xff;
+        data[12] ^= 0xff;
+
+        let message = ModesMessage::from_buffer_trusted(0, 0, data.to_vec()).expect("trusted frame should decode");
+        assert!(message.valid);
+        assert!(!message.corrected);
+    }
+
+    #[test]
+    fn from_buffer_trusted_still_rejects_a_frame_with_an_unsupported_length() {
+        let data = [0u8; 5];
+
+        assert_eq!(ModesMessage::from_buffer_trusted(0, 0, data.to_vec()), Err(DecodeError::WrongLength { len: 5 }));
+    }
+
+    #[test]
+    fn decode_message_rejects_an_illegal_gillham_altitude_encoding() {
+        let mut data = clean_df17_frame();
+        data[6] = 0x20; // non-zero AC12 field that decode_ac12 rejects as illegal Gillham
+
+        let crc = checksum(&data, Some(LONG_MSG_BITS));
+        data[11] = (crc >> 16) as u8;
+        data[12] = (crc >> 8) as u8;
+        data[13] = crc as u8;
+
+        assert_eq!(decode_message(0, 0, &data), Err(DecodeError::InvalidAltitude));
+    }
+
+    #[test]
+    fn diagnose_reports_a_clean_df17_frame() {
+        let diagnosis = diagnose(&clean_df17_frame());
+
+        assert_eq!(diagnosis.df, Some(17));
+        assert_eq!(diagnosis.expected_len, Some(14));
+        assert_eq!(diagnosis.actual_len, 14);
+        assert!(diagnosis.df_supported);
+        assert_eq!(diagnosis.crc_syndrome, Some(0));
+        assert_eq!(diagnosis.reason, "frame decodes cleanly");
+    }
+
+    #[test]
+    fn diagnose_reports_an_empty_payload() {
+        let diagnosis = diagnose(&[]);
+
+        assert_eq!(diagnosis.df, None);
+        assert_eq!(diagnosis.expected_len, None);
+        assert_eq!(diagnosis.actual_len, 0);
+        assert!(!diagnosis.df_supported);
+        assert_eq!(diagnosis.crc_syndrome, None);
+    }
+
+    #[test]
+    fn diagnose_reports_a_mode_ac_reply_as_always_accepted() {
+        let diagnosis = diagnose(&[0xab, 0xcd]);
+
+        assert_eq!(diagnosis.df, Some(DF_MODEAC));
+        assert_eq!(diagnosis.expected_len, Some(2));
+        assert!(diagnosis.df_supported);
+        assert_eq!(diagnosis.crc_syndrome, None);
+    }
+
+    #[test]
+    fn diagnose_reports_a_length_mismatch_without_computing_a_crc_syndrome() {
+        let mut data = [0u8; 7];
+        data[0] = (17 << 3) | 5; // DF17 needs 14 bytes, not 7
+
+        let diagnosis = diagnose(&data);
+
+        assert_eq!(diagnosis.df, Some(17));
+        assert_eq!(diagnosis.expected_len, Some(14));
+        assert_eq!(diagnosis.actual_len, 7);
+        assert!(diagnosis.df_supported);
+        assert_eq!(diagnosis.crc_syndrome, None);
+        assert!(diagnosis.reason.contains("14-byte"));
+    }
+
+    #[test]
+    fn diagnose_reports_an_unsupported_downlink_format() {
+        let mut data = [0u8; 14];
+        data[0] = 22 << 3; // DF22, not in the handled set
+
+        let diagnosis = diagnose(&data);
+
+        assert_eq!(diagnosis.df, Some(22));
+        assert!(!diagnosis.df_supported);
+        assert_eq!(diagnosis.crc_syndrome, None);
+    }
+
+    #[test]
+    fn diagnose_reports_an_uncorrectable_crc_syndrome() {
+        let mut data = clean_df17_frame();
+        data[11] ^= 0xff;
+        data[12] ^= 0xff;
+
+        let diagnosis = diagnose(&data);
+
+        assert_eq!(diagnosis.df, Some(17));
+        assert!(diagnosis.df_supported);
+        assert_ne!(diagnosis.crc_syndrome, Some(0));
+        assert_eq!(diagnosis.reason, DecodeError::CrcFailed.to_string());
+    }
+
+    // DF17 identification message, CA=5, ICAO 0xABCDEF, metype 4
+    // (aircraft category), spelling out "CRATE01" (padded to 8 chars).
+    fn clean_df17_ident_frame(callsign: &[u8; 8]) -> [u8; 14] {
+        let mut data = [0u8; 14];
+        data[0] = (17 << 3) | 5; // DF17, CA=5
+        data[1] = 0xab;
+        data[2] = 0xcd;
+        data[3] = 0xef;
+        data[4] = 4 << 3; // metype 4, category 0
+
+        let code_of = |c: u8| -> u32 {
+            CALLSIGN_CHARSET.iter().position(|&x| x == c).expect("char in charset") as u32
+        };
+        let codes: Vec<u32> = callsign.iter().map(|&c| code_of(c)).collect();
+        let chars1 = (codes[0] << 18) | (codes[1] << 12) | (codes[2] << 6) | codes[3];
+        let chars2 = (codes[4] << 18) | (codes[5] << 12) | (codes[6] << 6) | codes[7];
+        data[5] = (chars1 >> 16) as u8;
+        data[6] = (chars1 >> 8) as u8;
+        data[7] = chars1 as u8;
+        data[8] = (chars2 >> 16) as u8;
+        data[9] = (chars2 >> 8) as u8;
+        data[10] = chars2 as u8;
+
+        let crc = checksum(&data, Some(LONG_MSG_BITS));
+        data[11] = (crc >> 16) as u8;
+        data[12] = (crc >> 8) as u8;
+        data[13] = crc as u8;
+        data
+    }
+
+    #[test]
+    fn decode_callsign_trims_trailing_spaces() {
+        let data = [b'C', b'R', b'A', b'T', b'E', b'0', b'1', b' '];
+        assert_eq!(decode_callsign(&data[..]), Some("CRATE01".to_string()));
+    }
+
+    #[test]
+    fn decode_callsign_rejects_a_reserved_code() {
+        // 0x00 in the packed 6-bit stream maps to the reserved '?' entry.
+        assert_eq!(decode_callsign(&[0u8; 7]), None);
+    }
+
+    #[test]
+    fn decode_callsign_rejects_the_wrong_length() {
+        assert_eq!(decode_callsign(&[0u8; 6]), None);
+    }
+
+    #[test]
+    fn decode_populates_callsign_from_an_identification_message() {
+        let mut msg = ModesMessage::default();
+        msg.data = clean_df17_ident_frame(b"CRATE01 ").to_vec();
+        msg.datalen = msg.data.len();
+
+        msg.decode().unwrap();
+
+        assert!(msg.valid);
+        assert_eq!(msg.callsign, Some("CRATE01".to_string()));
+    }
+
+    // DF17 airborne velocity message (ME type 19), CA=5, ICAO 0xABCDEF,
+    // ground speed subtype 1: 100kt east-west, 100kt north-south (heading
+    // 045), climbing at 640fpm.
+    fn clean_df17_velocity_frame() -> [u8; 14] {
+        let mut data = [0u8; 14];
+        data[0] = (17 << 3) | 5; // DF17, CA=5
+        data[1] = 0xab;
+        data[2] = 0xcd;
+        data[3] = 0xef;
+        data[4] = (19 << 3) | 1; // metype 19, subtype 1
+
+        let ew_raw: u32 = 101; // ew_vel = 100kt, east (dir bit clear)
+        let ns_raw: u32 = 101; // ns_vel = 100kt, north (dir bit clear)
+        let vert_raw: u32 = 11; // (raw - 1) * 64 = 640fpm, climbing (sign bit clear)
+
+        data[5] = ((ew_raw >> 8) & 0x03) as u8;
+        data[6] = (ew_raw & 0xff) as u8;
+        data[7] = ((ns_raw >> 3) & 0x7f) as u8;
+        data[8] = (((ns_raw & 0x07) as u8) << 5) | ((vert_raw >> 6) & 0x07) as u8;
+        data[9] = ((vert_raw & 0x3f) << 2) as u8;
+
+        let crc = checksum(&data, Some(LONG_MSG_BITS));
+        data[11] = (crc >> 16) as u8;
+        data[12] = (crc >> 8) as u8;
+        data[13] = crc as u8;
+        data
+    }
+
+    #[test]
+    fn decode_vertical_rate_rejects_the_not_available_encoding() {
+        assert_eq!(decode_vertical_rate(&[0u8; 7]), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn decode_populates_ground_speed_track_and_vertical_rate_from_a_velocity_message() {
+        let mut msg = ModesMessage::default();
+        msg.data = clean_df17_velocity_frame().to_vec();
+        msg.datalen = msg.data.len();
+
+        msg.decode().unwrap();
+
+        assert!(msg.valid);
+        assert_eq!(msg.ground_speed, Some(141)); // sqrt(100^2 + 100^2)
+        assert!((msg.track.unwrap() - 45.0).abs() < 0.5);
+        assert_eq!(msg.vertical_rate, Some(640));
+    }
+
+    #[test]
+    fn decode_identity_decodes_the_7500_hijack_emergency_code() {
+        // A=7, B=5, C=0, D=0.
+        assert_eq!(decode_identity(&[0x0a, 0xa2]), 7500);
+    }
+
+    #[test]
+    fn decode_identity_decodes_the_7600_radio_failure_emergency_code() {
+        // A=7, B=6, C=0, D=0.
+        assert_eq!(decode_identity(&[0x0a, 0x8a]), 7600);
+    }
+
+    #[test]
+    fn decode_identity_decodes_the_7700_general_emergency_code() {
+        // A=7, B=0, C=0, D=0.
+        assert_eq!(decode_identity(&[0x0a, 0x80]), 7700);
+    }
+
+    #[test]
+    fn decode_identity_decodes_the_1200_vfr_code() {
+        // A=1, B=2, C=0, D=0.
+        assert_eq!(decode_identity(&[0x08, 0x08]), 1200);
+    }
+
+    #[test]
+    fn decode_identity_decodes_zero_for_an_all_clear_field() {
+        assert_eq!(decode_identity(&[0x00, 0x00]), 0);
+    }
+
+    #[test]
+    fn decode_ac13_returns_none_for_altitude_not_available() {
+        assert_eq!(decode_ac13(0), None);
+    }
+
+    #[test]
+    fn decode_ac13_returns_none_when_the_m_bit_is_set() {
+        assert_eq!(decode_ac13(0x0040), None);
+    }
+
+    #[test]
+    fn decode_ac13_returns_none_for_an_illegal_gillham_code() {
+        // Q bit clear (not metric altitude), and both C1/C2/C4 (0x1500)
+        // clear, which the Gillham decoding rejects outright.
+        assert_eq!(decode_ac13(0x0001), None);
+    }
+
+    #[test]
+    fn decode_ac12_returns_none_for_altitude_not_available() {
+        assert_eq!(decode_ac12(0), None);
+    }
+
+    #[test]
+    fn decode_short_air_air_status_extracts_vs_sl_and_ri() {
+        // VS set (on ground), SL=3 (top 3 bits of data[1]), RI=0b1011 (top
+        // 3 bits split across data[1]'s low 3 bits and data[2]'s top bit).
+        let data = [0x04, 0x65, 0x80, 0x00];
+        assert_eq!(decode_short_air_air_status(&data), (true, 3, 0b1011));
+    }
+
+    #[test]
+    fn decode_does_not_panic_on_a_surveillance_reply_with_no_altitude() {
+        // DF4, altitude-not-available AC13 field (all zero).
+        let mut data = [0u8; 7];
+        data[0] = 4 << 3;
+        data[1] = 0xab;
+        data[2] = 0x00;
+        data[3] = 0x00;
+        // DF4/16/20's CRC is xored with the sender's ICAO address rather
+        // than left at zero, and any residual is accepted as the decoded
+        // address (there is nothing to correct), so this frame is valid
+        // regardless of what the trailing CRC/address bytes happen to be.
+
+        let mut msg = ModesMessage::default();
+        msg.data = data.to_vec();
+        msg.datalen = msg.data.len();
+
+        msg.decode().unwrap();
+
+        assert!(msg.valid);
+        assert_eq!(msg.altitude, 0);
+    }
+
+    #[test]
+    fn decode_populates_squawk_from_a_df5_surveillance_reply() {
+        // DF5, ID13 field encoding the 7500 hijack emergency code.
+        let mut data = [0u8; 7];
+        data[0] = 5 << 3;
+        data[1] = 0xab;
+        data[2] = 0x0a;
+        data[3] = 0xa2;
+
+        let mut msg = ModesMessage::default();
+        msg.data = data.to_vec();
+        msg.datalen = msg.data.len();
+
+        msg.decode().unwrap();
+
+        assert!(msg.valid);
+        assert_eq!(msg.squawk, Some(7500));
+    }
+
+    // DF11 all-call reply, ICAO 0xABCDEF, with `code` XORed into the low 7
+    // bits of the parity overlay as the II/SI interrogator code.
+    fn df11_frame(code: u8) -> [u8; 7] {
+        let mut data = [0u8; 7];
+        data[0] = 11 << 3; // DF11, CA=0
+        data[1] = 0xab;
+        data[2] = 0xcd;
+        data[3] = 0xef;
+
+        let crc = checksum(&data, Some(SHORT_MSG_BITS)) ^ (code as u32 & 0x7f);
+        data[4] = (crc >> 16) as u8;
+        data[5] = (crc >> 8) as u8;
+        data[6] = crc as u8;
+        data
+    }
+
+    #[test]
+    fn df11_interrogator_returns_none_for_a_zero_residual() {
+        let frame = df11_frame(0);
+        assert_eq!(crc_residual(&frame, Some(SHORT_MSG_BITS)), 0);
+        assert_eq!(df11_interrogator(&frame), None);
+    }
+
+    #[test]
+    fn df11_interrogator_extracts_a_nonzero_ii_code() {
+        assert_eq!(df11_interrogator(&df11_frame(5)), Some(5));
+    }
+
+    #[test]
+    fn decode_populates_interrogator_from_a_df11_reply_with_a_nonzero_ii_code() {
+        let mut msg = ModesMessage::default();
+        msg.data = df11_frame(5).to_vec();
+        msg.datalen = msg.data.len();
+
+        msg.decode().expect("valid DF11 frame decodes");
+
+        assert!(msg.valid);
+        assert_eq!(msg.interrogator, Some(5));
+    }
+
+    #[test]
+    fn decode_leaves_interrogator_none_for_a_df11_reply_with_ic_zero() {
+        let mut msg = ModesMessage::default();
+        msg.data = df11_frame(0).to_vec();
+        msg.datalen = msg.data.len();
+
+        msg.decode().expect("clean DF11 frame decodes");
+
+        assert!(msg.valid);
+        assert_eq!(msg.interrogator, None);
+    }
+
+    #[test]
+    fn decode_populates_vs_sl_ri_from_a_df0_short_air_air_surveillance_reply() {
+        // DF0, VS set (on ground), SL=3, RI=0b1011, altitude-not-available.
+        let mut data = [0u8; 7];
+        data[0] = 0x04; // DF=0, VS=1
+        data[1] = 0x65; // SL=3, RI top 3 bits = 0b101
+        data[2] = 0x80; // RI low bit = 1, AC top 5 bits = 0
+
+        let mut msg = ModesMessage::default();
+        msg.data = data.to_vec();
+        msg.datalen = msg.data.len();
+
+        msg.decode().unwrap();
+
+        assert!(msg.valid);
+        assert_eq!(msg.on_ground, Some(true));
+        assert_eq!(msg.sensitivity_level, Some(3));
+        assert_eq!(msg.reply_information, Some(0b1011));
+        assert_eq!(msg.altitude, 0);
+    }
+
+    #[test]
+    fn decode_populates_vs_sl_ri_from_a_df16_long_air_air_surveillance_reply() {
+        // DF16, VS clear (airborne), SL=7, RI=0.
+        let mut data = [0u8; 14];
+        data[0] = 16 << 3; // DF=16, VS=0
+        data[1] = 0xE0; // SL=7
+
+        let mut msg = ModesMessage::default();
+        msg.data = data.to_vec();
+        msg.datalen = msg.data.len();
+
+        msg.decode().unwrap();
+
+        assert!(msg.valid);
+        assert_eq!(msg.on_ground, Some(false));
+        assert_eq!(msg.sensitivity_level, Some(7));
+        assert_eq!(msg.reply_information, Some(0));
+    }
+
+    #[test]
+    fn decode_does_not_populate_air_air_status_fields_for_a_df4_surveillance_reply() {
+        let mut data = [0u8; 7];
+        data[0] = 4 << 3;
+
+        let mut msg = ModesMessage::default();
+        msg.data = data.to_vec();
+        msg.datalen = msg.data.len();
+
+        msg.decode().unwrap();
+
+        assert!(msg.valid);
+        assert_eq!(msg.on_ground, None);
+        assert_eq!(msg.sensitivity_level, None);
+        assert_eq!(msg.reply_information, None);
+    }
+
+    #[test]
+    fn decode_does_not_panic_on_a_position_message_with_no_altitude() {
+        let mut data = clean_df17_frame();
+        data[5] = 0x00; // AC12 = 0, altitude not available
+        let crc = checksum(&data, Some(LONG_MSG_BITS));
+        data[11] = (crc >> 16) as u8;
+        data[12] = (crc >> 8) as u8;
+        data[13] = crc as u8;
+
+        let mut msg = ModesMessage::default();
+        msg.data = data.to_vec();
+        msg.datalen = msg.data.len();
+
+        msg.decode().unwrap();
+
+        assert!(msg.valid);
+        assert_eq!(msg.altitude, 0);
+    }
+
+    #[test]
+    fn display_summarizes_a_valid_frame_with_decoded_fields() {
+        let mut msg = ModesMessage::default();
+        msg.data = clean_df17_ident_frame(b"CRATE01 ").to_vec();
+        msg.datalen = msg.data.len();
+        msg.decode().unwrap();
+
+        let summary = msg.to_string();
+        assert_eq!(summary, "DF17 ABCDEF ident=CRATE01");
+    }
+
+    #[test]
+    fn display_falls_back_to_hex_for_an_invalid_frame() {
+        let mut msg = ModesMessage::default();
+        msg.data = vec![0x8du8, 0xab, 0xcd]; // DF17 but way too short
+        msg.datalen = msg.data.len();
+        let _ = msg.decode();
+
+        assert_eq!(msg.to_string(), "8dabcd");
+    }
+
+    #[test]
+    fn new_timestamp_jump_event_records_the_previous_timestamp_and_delta() {
+        let msg = new_timestamp_jump_event(1_000_000, 999_400);
+
+        assert_eq!(msg.df, DF_EVENT_TIMESTAMP_JUMP);
+        assert_eq!(msg.timestamp, 1_000_000);
+        assert_eq!(msg.eventdata.get("previous_timestamp"), Some(&EventData::Int(999_400)));
+        assert_eq!(msg.eventdata.get("delta"), Some(&EventData::Int(600)));
+    }
+
+    #[test]
+    fn new_epoch_rollover_event_carries_the_rollover_count() {
+        let msg = new_epoch_rollover_event(42, 3);
+
+        assert_eq!(msg.df, DF_EVENT_EPOCH_ROLLOVER);
+        assert_eq!(msg.timestamp, 42);
+        assert_eq!(msg.eventdata.get("rollover_count"), Some(&EventData::Int(3)));
+    }
+
+    #[test]
+    fn new_event_message_matches_the_internal_eventmessage_constructor() {
+        let mut eventdata = BTreeMap::new();
+        eventdata.insert("note".to_string(), EventData::Text("hello".to_string()));
+
+        let msg = ModesMessage::new_event_message(DF_EVENT_MODE_CHANGE, 7, eventdata.clone());
+
+        assert_eq!(msg.df, DF_EVENT_MODE_CHANGE);
+        assert_eq!(msg.timestamp, 7);
+        assert_eq!(msg.eventdata, eventdata);
+    }
+
+    #[test]
+    fn timestamp_jump_detector_ignores_the_first_timestamp_it_sees() {
+        let mut detector = TimestampJumpDetector::new();
+        assert_eq!(detector.update(1_000_000), None);
+    }
+
+    #[test]
+    fn timestamp_jump_detector_ignores_ordinary_elapsed_time() {
+        let mut detector = TimestampJumpDetector::with_threshold(1_000);
+        detector.update(1_000_000);
+        assert_eq!(detector.update(1_000_500), None);
+    }
+
+    #[test]
+    fn timestamp_jump_detector_reports_a_forward_jump() {
+        let mut detector = TimestampJumpDetector::with_threshold(1_000);
+        detector.update(1_000_000);
+
+        let event = detector.update(2_000_000).expect("jump should be reported");
+        assert_eq!(event.df, DF_EVENT_TIMESTAMP_JUMP);
+        assert_eq!(event.eventdata.get("previous_timestamp"), Some(&EventData::Int(1_000_000)));
+        assert_eq!(event.eventdata.get("delta"), Some(&EventData::Int(1_000_000)));
+    }
+
+    #[test]
+    fn timestamp_jump_detector_reports_a_backward_jump() {
+        let mut detector = TimestampJumpDetector::with_threshold(1_000);
+        detector.update(1_000_000);
+
+        let event = detector.update(500_000).expect("jump should be reported");
+        assert_eq!(event.eventdata.get("delta"), Some(&EventData::Int(-500_000)));
+    }
+
+    #[test]
+    fn timestamp_jump_detector_reports_an_epoch_rollover_instead_of_a_jump() {
+        let mut detector = TimestampJumpDetector::with_threshold(1_000);
+        detector.update(TIMESTAMP_EPOCH_TICKS - 1);
+
+        let event = detector.update(500).expect("rollover should be reported");
+        assert_eq!(event.df, DF_EVENT_EPOCH_ROLLOVER);
+        assert_eq!(event.timestamp, 500);
+        assert_eq!(event.eventdata.get("rollover_count"), Some(&EventData::Int(1)));
+    }
+
+    #[test]
+    fn timestamp_jump_detector_counts_repeated_rollovers_in_a_descending_then_wrapping_sequence() {
+        let mut detector = TimestampJumpDetector::with_threshold(1_000);
+
+        // A small descending step near the top of the range (e.g. clock
+        // drift correction) shouldn't be mistaken for a rollover...
+        detector.update(TIMESTAMP_EPOCH_TICKS - 500);
+        assert_eq!(detector.update(TIMESTAMP_EPOCH_TICKS - 800), None);
+
+        // ...but wrapping past the top of the range, twice, should be
+        // reported both times with an increasing count.
+        let first = detector.update(100).expect("first rollover should be reported");
+        assert_eq!(first.eventdata.get("rollover_count"), Some(&EventData::Int(1)));
+
+        detector.update(TIMESTAMP_EPOCH_TICKS - 200);
+        let second = detector.update(300).expect("second rollover should be reported");
+        assert_eq!(second.eventdata.get("rollover_count"), Some(&EventData::Int(2)));
+    }
+
+    #[test]
+    fn timestamp_jump_detector_reports_a_rollover_under_the_12mhz_modulus() {
+        let mut detector = TimestampJumpDetector::with_threshold_and_modulus(1_000, TIMESTAMP_EPOCH_TICKS);
+        detector.update(TIMESTAMP_EPOCH_TICKS - 1);
+
+        let event = detector.update(500).expect("rollover should be reported");
+        assert_eq!(event.df, DF_EVENT_EPOCH_ROLLOVER);
+        assert_eq!(event.eventdata.get("rollover_count"), Some(&EventData::Int(1)));
+    }
+
+    #[test]
+    fn timestamp_jump_detector_reports_a_rollover_under_the_gps_nanos_modulus() {
+        // GPS-ns timestamps reset at UTC midnight rather than wrapping a
+        // 48-bit counter, so the same near-the-top-then-near-zero pattern
+        // has to be judged against `GPS_NANOS_EPOCH_TICKS`, not
+        // `TIMESTAMP_EPOCH_TICKS` -- a threshold/timestamp pair that would
+        // be an ordinary jump under one modulus is a rollover under the
+        // other.
+        let mut detector = TimestampJumpDetector::with_threshold_and_modulus(1_000, GPS_NANOS_EPOCH_TICKS);
+        detector.update(GPS_NANOS_EPOCH_TICKS - 1);
+
+        let event = detector.update(500).expect("rollover should be reported");
+        assert_eq!(event.df, DF_EVENT_EPOCH_ROLLOVER);
+        assert_eq!(event.eventdata.get("rollover_count"), Some(&EventData::Int(1)));
+
+        // The same jump, interpreted under the 12MHz modulus, is nowhere
+        // near that counter's top and is instead just a big ordinary jump.
+        let mut mhz12_detector = TimestampJumpDetector::with_threshold(1_000);
+        mhz12_detector.update(GPS_NANOS_EPOCH_TICKS - 1);
+        let mhz12_event = mhz12_detector.update(500).expect("jump should be reported");
+        assert_eq!(mhz12_event.df, DF_EVENT_TIMESTAMP_JUMP);
+    }
+
+    #[test]
+    fn nucp_to_rc_looks_up_the_do_260_containment_radius() {
+        assert_eq!(nucp_to_rc(0), Some(f64::INFINITY));
+        assert_eq!(nucp_to_rc(7), Some(1852.0));
+        assert_eq!(nucp_to_rc(9), Some(185.2));
+    }
+
+    #[test]
+    fn nucp_to_rc_rejects_a_value_outside_the_defined_table() {
+        assert_eq!(nucp_to_rc(10), None);
+    }
+
+    #[test]
+    fn nic_mirrors_nuc_for_this_decoders_adsb_version_0_messages() {
+        let mut msg = ModesMessage::default();
+        msg.nuc = 7;
+        assert_eq!(msg.nic(), 7);
+    }
+
+    #[test]
+    fn icao_hex_formats_a_valid_df17_address_as_uppercase_hex() {
+        let msg = ModesMessage::from_buffer(0, 0, clean_df17_frame().to_vec()).unwrap();
+        assert_eq!(msg.icao_hex(), "ABCDEF");
+    }
+
+    #[test]
+    fn icao_hex_masks_off_bits_above_the_low_24() {
+        let mut msg = ModesMessage::default();
+        msg.valid = true;
+        msg.df = 17;
+        msg.address = -1; // all bits set
+        assert_eq!(msg.icao_hex(), "FFFFFF");
+    }
+
+    #[test]
+    fn icao_hex_is_empty_for_a_mode_ac_reply() {
+        let msg = ModesMessage::from_buffer(0, 0, vec![0x12, 0x34]).unwrap();
+        assert_eq!(msg.icao_hex(), "");
+    }
+
+    #[test]
+    fn icao_hex_is_empty_for_an_invalid_message() {
+        let mut msg = ModesMessage::default();
+        msg.valid = false;
+        assert_eq!(msg.icao_hex(), "");
+    }
+
+    #[test]
+    fn to_avr_emits_an_at_line_with_a_twelve_digit_timestamp_when_non_zero() {
+        let msg = ModesMessage::from_buffer(0x123456789ABC, 0, clean_df17_frame().to_vec()).unwrap();
+        let hex: String = clean_df17_frame().iter().map(|b| format!("{:02X}", b)).collect();
+        assert_eq!(msg.to_avr(), format!("@123456789ABC{};", hex));
+    }
+
+    #[test]
+    fn to_avr_emits_a_star_line_when_the_timestamp_is_zero() {
+        let msg = ModesMessage::from_buffer(0, 0, clean_df17_frame().to_vec()).unwrap();
+        let hex: String = clean_df17_frame().iter().map(|b| format!("{:02X}", b)).collect();
+        assert_eq!(msg.to_avr(), format!("*{};", hex));
+    }
+
+    #[test]
+    fn on_ground_is_none_for_an_invalid_message() {
+        let mut msg = ModesMessage::default();
+        msg.valid = false;
+        assert_eq!(msg.on_ground(), None);
+    }
+
+    #[test]
+    fn on_ground_is_false_for_a_df17_airborne_message_via_ca() {
+        let msg = ModesMessage::from_buffer(0, 0, clean_df17_frame().to_vec()).unwrap(); // CA=5
+        assert_eq!(msg.on_ground(), Some(false));
+    }
+
+    #[test]
+    fn on_ground_is_true_for_a_df17_surface_position_message_regardless_of_ca() {
+        let msg = ModesMessage::from_buffer(0, 0, clean_df17_surface_frame().to_vec()).unwrap(); // CA=5, metype 6
+        assert_eq!(msg.on_ground(), Some(true));
+    }
+
+    #[test]
+    fn on_ground_mirrors_the_vs_bit_for_a_df0_short_air_air_reply() {
+        let mut data = [0u8; 7];
+        data[0] = 0x04; // DF=0, VS=1 (on ground)
+        let mut msg = ModesMessage::default();
+        msg.data = data.to_vec();
+        msg.datalen = msg.data.len();
+        msg.decode().unwrap();
+
+        assert_eq!(msg.on_ground(), Some(true));
+    }
+
+    #[test]
+    fn on_ground_decodes_fs_for_a_df5_surveillance_reply() {
+        let mut data = [0u8; 7];
+        data[0] = (5 << 3) | 1; // DF=5, FS=1 (normal, ground)
+        let mut msg = ModesMessage::default();
+        msg.data = data.to_vec();
+        msg.datalen = msg.data.len();
+        msg.decode().unwrap();
+
+        assert_eq!(msg.on_ground(), Some(true));
+    }
+
+    #[test]
+    fn on_ground_is_none_for_an_fs_value_that_does_not_distinguish_airborne_from_ground() {
+        let mut data = [0u8; 7];
+        data[0] = (4 << 3) | 4; // DF=4, FS=4 (alert & SPI, airborne or on the ground)
+        let mut msg = ModesMessage::default();
+        msg.data = data.to_vec();
+        msg.datalen = msg.data.len();
+        msg.decode().unwrap();
+
+        assert_eq!(msg.on_ground(), None);
+    }
+
+    // DF17 operational status message, CA=5, ICAO 0xABCDEF, metype 31,
+    // ADS-B version 2, NIC supplement-A set, NACp 9, SIL 2.
+    fn clean_df17_opstatus_frame(subtype: u8) -> [u8; 14] {
+        let mut data = [0u8; 14];
+        data[0] = (17 << 3) | 5; // DF17, CA=5
+        data[1] = 0xab;
+        data[2] = 0xcd;
+        data[3] = 0xef;
+        data[4] = (31 << 3) | subtype;
+        data[5] = 0x00;
+        data[6] = 0x00;
+        data[7] = 0x00;
+        data[8] = 0x00;
+        data[9] = (2 << 5) | (1 << 4) | 9; // version=2, NIC supp-A=1, NACp=9
+        data[10] = 2 << 4; // SIL=2
+
+        let crc = checksum(&data, Some(LONG_MSG_BITS));
+        data[11] = (crc >> 16) as u8;
+        data[12] = (crc >> 8) as u8;
+        data[13] = crc as u8;
+        data
+    }
+
+    #[test]
+    fn decode_populates_version_nacp_and_sil_from_an_airborne_operational_status_message() {
+        let msg = ModesMessage::from_buffer(0, 0, clean_df17_opstatus_frame(0).to_vec()).unwrap();
+        assert!(msg.valid);
+        assert_eq!(msg.adsb_version, Some(2));
+        assert_eq!(msg.nac_p, Some(9));
+        assert_eq!(msg.sil, Some(2));
+    }
+
+    #[test]
+    fn decode_populates_version_nacp_and_sil_from_a_surface_operational_status_message() {
+        let msg = ModesMessage::from_buffer(0, 0, clean_df17_opstatus_frame(1).to_vec()).unwrap();
+        assert!(msg.valid);
+        assert_eq!(msg.adsb_version, Some(2));
+        assert_eq!(msg.nac_p, Some(9));
+        assert_eq!(msg.sil, Some(2));
+    }
+
+    #[test]
+    fn decode_leaves_version_nacp_and_sil_unset_for_a_non_opstatus_message() {
+        let msg = ModesMessage::from_buffer(0, 0, clean_df17_frame().to_vec()).unwrap();
+        assert_eq!(msg.adsb_version, None);
+        assert_eq!(msg.nac_p, None);
+        assert_eq!(msg.sil, None);
+    }
+
+    // DF18 airborne position message, CF as given, metype 11, otherwise
+    // identical to `clean_df17_frame`.
+    fn clean_df18_frame(cf: u8) -> [u8; 14] {
+        let mut data = clean_df17_frame();
+        data[0] = (18 << 3) | cf;
+        let crc = checksum(&data, Some(LONG_MSG_BITS));
+        data[11] = (crc >> 16) as u8;
+        data[12] = (crc >> 8) as u8;
+        data[13] = crc as u8;
+        data
+    }
+
+    #[test]
+    fn decode_does_not_flag_a_df17_message_as_tisb_or_adsr() {
+        let msg = ModesMessage::from_buffer(0, 0, clean_df17_frame().to_vec()).unwrap();
+        assert!(!msg.is_tisb);
+        assert!(!msg.is_adsr);
+    }
+
+    #[test]
+    fn decode_does_not_flag_a_df18_cf0_message_as_tisb_or_adsr() {
+        // CF 0: ADS-B message from a non-transponder ADS-B participant with
+        // its own ICAO address -- a self-reported position, same as DF17.
+        let msg = ModesMessage::from_buffer(0, 0, clean_df18_frame(0).to_vec()).unwrap();
+        assert!(msg.valid);
+        assert!(!msg.is_tisb);
+        assert!(!msg.is_adsr);
+    }
+
+    #[test]
+    fn decode_flags_df18_cf2_through_cf5_as_tisb() {
+        for cf in [2u8, 3, 4, 5] {
+            let msg = ModesMessage::from_buffer(0, 0, clean_df18_frame(cf).to_vec()).unwrap();
+            assert!(msg.valid, "cf {}", cf);
+            assert!(msg.is_tisb, "cf {}", cf);
+            assert!(!msg.is_adsr, "cf {}", cf);
+        }
+    }
+
+    #[test]
+    fn decode_flags_df18_cf6_as_adsr() {
+        let msg = ModesMessage::from_buffer(0, 0, clean_df18_frame(6).to_vec()).unwrap();
+        assert!(msg.valid);
+        assert!(msg.is_adsr);
+        assert!(!msg.is_tisb);
+    }
+
+    #[test]
+    fn decode_surface_movement_rejects_the_not_available_encoding() {
+        assert_eq!(decode_surface_movement(0), None);
+    }
+
+    #[test]
+    fn decode_surface_movement_treats_1_as_stopped() {
+        assert_eq!(decode_surface_movement(1), Some(0.0));
+    }
+
+    #[test]
+    fn decode_surface_movement_rejects_the_reserved_high_values() {
+        assert_eq!(decode_surface_movement(125), None);
+        assert_eq!(decode_surface_movement(127), None);
+    }
+
+    #[test]
+    fn decode_surface_movement_reports_over_175kt_at_124() {
+        assert_eq!(decode_surface_movement(124), Some(175.0));
+    }
+
+    // DF17 surface position, CA=5, ICAO 0xABCDEF, metype 6, stopped
+    // (movement=1), track valid at 90 degrees, even CPR. The `T`/`F`/CPR
+    // fields sit at the same bit offsets as the airborne format, so this
+    // reuses the same CPR values as `clean_df17_frame`.
+    fn clean_df17_surface_frame() -> [u8; 14] {
+        let mut data = [0u8; 14];
+        data[0] = (17 << 3) | 5; // DF17, CA=5
+        data[1] = 0xab;
+        data[2] = 0xcd;
+        data[3] = 0xef;
+        data[4] = 6 << 3; // metype 6, movement high bits = 0
+        data[5] = 0x1a; // movement=1 (stopped), track valid, track high bits = 2
+        data[6] = 0x00; // track low bits = 0, even CPR (bit 0x04 clear)
+        data[7] = 0x12;
+        data[8] = 0x34;
+        data[9] = 0x56;
+        data[10] = 0x78;
+
+        let crc = checksum(&data, Some(LONG_MSG_BITS));
+        data[11] = (crc >> 16) as u8;
+        data[12] = (crc >> 8) as u8;
+        data[13] = crc as u8;
+        data
+    }
+
+    #[test]
+    fn decode_populates_movement_track_and_cpr_fields_from_a_surface_position_message() {
+        let mut msg = ModesMessage::default();
+        msg.data = clean_df17_surface_frame().to_vec();
+        msg.datalen = msg.data.len();
+
+        msg.decode().unwrap();
+
+        assert!(msg.valid);
+        assert!(msg.surface);
+        assert_eq!(msg.nuc, 14 - 6);
+        assert_eq!(msg.ground_speed, Some(0));
+        assert_eq!(msg.track, Some(90.0));
+        assert!(msg.even_cpr);
+        assert!(!msg.odd_cpr);
+        assert_eq!(msg.cpr_lat, 0x91a);
+        assert_eq!(msg.cpr_lon, 0x5678);
+    }
+
+    #[test]
+    fn content_key_matches_for_the_same_payload_heard_at_different_times() {
+        let mut first = ModesMessage::default();
+        first.data = clean_df17_frame().to_vec();
+        first.datalen = first.data.len();
+        first.timestamp = 1;
+        first.signal = 10;
+
+        let mut second = ModesMessage::default();
+        second.data = clean_df17_frame().to_vec();
+        second.datalen = second.data.len();
+        second.timestamp = 2;
+        second.signal = 20;
+
+        assert_eq!(first.content_key(), second.content_key());
+    }
+
+    #[test]
+    fn content_key_differs_for_different_payloads() {
+        let mut first = ModesMessage::default();
+        first.data = clean_df17_frame().to_vec();
+        first.datalen = first.data.len();
+
+        let mut second = ModesMessage::default();
+        second.data = clean_df17_ident_frame(b"CRATE01 ").to_vec();
+        second.datalen = second.data.len();
+
+        assert_ne!(first.content_key(), second.content_key());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn content_key_can_dedupe_repeat_receptions_in_a_hashset() {
+        use std::collections::HashSet;
+
+        let mut first = ModesMessage::default();
+        first.data = clean_df17_frame().to_vec();
+        first.datalen = first.data.len();
+        first.timestamp = 1;
+
+        let mut second = ModesMessage::default();
+        second.data = clean_df17_frame().to_vec();
+        second.datalen = second.data.len();
+        second.timestamp = 2;
+
+        let mut seen: HashSet<Vec<u8>> = HashSet::new();
+        assert!(seen.insert(first.content_key().to_vec()));
+        assert!(!seen.insert(second.content_key().to_vec()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn ord_sorts_messages_by_length_then_bytes_in_a_btreeset() {
+        use std::collections::BTreeSet;
+
+        let mut short = ModesMessage::default();
+        short.data = vec![0xAA; 2];
+        short.datalen = short.data.len();
+
+        let mut long_a = ModesMessage::default();
+        long_a.data = clean_df17_frame().to_vec();
+        long_a.datalen = long_a.data.len();
+
+        let mut long_b = ModesMessage::default();
+        long_b.data = clean_df17_ident_frame(b"CRATE01 ").to_vec();
+        long_b.datalen = long_b.data.len();
+        let (long_low, long_high) =
+            if long_a.content_key() < long_b.content_key() { (long_a, long_b) } else { (long_b, long_a) };
+
+        let mut set = BTreeSet::new();
+        set.insert(long_high.clone());
+        set.insert(short.clone());
+        set.insert(long_low.clone());
+        // Re-inserting the same bytes (with a different timestamp) should
+        // not grow the set: `Ord`/`Eq` here are content-only.
+        let mut duplicate = short.clone();
+        duplicate.timestamp = 999;
+        set.insert(duplicate);
+
+        let ordered: Vec<&ModesMessage> = set.iter().collect();
+        assert_eq!(ordered.len(), 3);
+        assert_eq!(ordered[0].datalen, 2);
+        assert_eq!(ordered[1].content_key(), long_low.content_key());
+        assert_eq!(ordered[2].content_key(), long_high.content_key());
+    }
+
+    #[test]
+    fn ticks_since_computes_ordinary_elapsed_time() {
+        assert_eq!(ticks_since(1_000_500, 1_000_000), 500);
+    }
+
+    #[test]
+    fn ticks_since_accounts_for_an_epoch_rollover() {
+        assert_eq!(ticks_since(100, TIMESTAMP_EPOCH_TICKS - 400), 500);
+    }
+
+    #[test]
+    fn clock_ref_maps_the_reference_ticks_to_the_reference_time() {
+        let reference_time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        let clock = ClockRef::new(reference_time, 1_000_000);
+        assert_eq!(clock.ticks_to_systemtime(1_000_000), reference_time);
+    }
+
+    #[test]
+    fn clock_ref_maps_elapsed_ticks_to_a_duration_offset() {
+        let reference_time = std::time::UNIX_EPOCH;
+        let clock = ClockRef::new(reference_time, 0);
+        let one_second_of_ticks = TICKS_PER_SECOND as u64;
+        assert_eq!(
+            clock.ticks_to_systemtime(one_second_of_ticks),
+            reference_time + std::time::Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn clock_ref_accounts_for_an_epoch_rollover_between_frames() {
+        let reference_time = std::time::UNIX_EPOCH;
+        let clock = ClockRef::new(reference_time, TIMESTAMP_EPOCH_TICKS - 400);
+        assert_eq!(
+            clock.ticks_to_systemtime(100),
+            reference_time + std::time::Duration::from_secs_f64(500.0 / TICKS_PER_SECOND)
+        );
+    }
+
+    fn frame_at(timestamp: u64, payload: [u8; 14]) -> ModesMessage {
+        let mut msg = ModesMessage::default();
+        msg.data = payload.to_vec();
+        msg.datalen = msg.data.len();
+        msg.timestamp = timestamp;
+        msg
+    }
+
+    #[test]
+    fn dedup_window_forwards_the_first_reception_of_a_payload() {
+        let mut dedup = DedupWindow::with_window(1_000);
+        assert!(dedup.observe(&frame_at(0, clean_df17_frame())));
+    }
+
+    #[test]
+    fn dedup_window_suppresses_a_duplicate_seen_within_the_window() {
+        let mut dedup = DedupWindow::with_window(1_000);
+        assert!(dedup.observe(&frame_at(0, clean_df17_frame())));
+        assert!(!dedup.observe(&frame_at(500, clean_df17_frame())));
+    }
+
+    #[test]
+    fn dedup_window_forwards_the_same_payload_again_once_the_window_elapses() {
+        let mut dedup = DedupWindow::with_window(1_000);
+        assert!(dedup.observe(&frame_at(0, clean_df17_frame())));
+        assert!(dedup.observe(&frame_at(1_500, clean_df17_frame())));
+    }
+
+    #[test]
+    fn dedup_window_treats_different_payloads_independently() {
+        let mut dedup = DedupWindow::with_window(1_000);
+        assert!(dedup.observe(&frame_at(0, clean_df17_frame())));
+        assert!(dedup.observe(&frame_at(0, clean_df17_ident_frame(b"CRATE01 "))));
+    }
+
+    #[test]
+    fn dedup_window_handles_a_duplicate_seen_just_after_an_epoch_rollover() {
+        let mut dedup = DedupWindow::with_window(1_000);
+        assert!(dedup.observe(&frame_at(TIMESTAMP_EPOCH_TICKS - 400, clean_df17_frame())));
+        assert!(!dedup.observe(&frame_at(100, clean_df17_frame())));
+    }
+
+    fn stats_frame(df: u32, valid: bool, corrected: bool) -> ModesMessage {
+        let mut msg = ModesMessage::default();
+        msg.df = df;
+        msg.valid = valid;
+        msg.corrected = corrected;
+        msg
+    }
+
+    #[test]
+    fn stats_counts_a_valid_frame() {
+        let mut stats = Stats::new();
+        stats.record(&stats_frame(17, true, false));
+        assert_eq!(stats.total_frames(), 1);
+        assert_eq!(stats.valid_frames(), 1);
+        assert_eq!(stats.crc_failures(), 0);
+        assert_eq!(stats.corrected_frames(), 0);
+        assert_eq!(stats.frames_for_df(17), 1);
+    }
+
+    #[test]
+    fn stats_counts_an_invalid_frame_as_a_crc_failure() {
+        let mut stats = Stats::new();
+        stats.record(&stats_frame(17, false, false));
+        assert_eq!(stats.total_frames(), 1);
+        assert_eq!(stats.valid_frames(), 0);
+        assert_eq!(stats.crc_failures(), 1);
+        assert_eq!(stats.frames_for_df(17), 0);
+    }
+
+    #[test]
+    fn stats_counts_a_corrected_frame_as_both_valid_and_corrected() {
+        let mut stats = Stats::new();
+        stats.record(&stats_frame(11, true, true));
+        assert_eq!(stats.valid_frames(), 1);
+        assert_eq!(stats.corrected_frames(), 1);
+    }
+
+    #[test]
+    fn stats_breaks_down_valid_frames_by_df() {
+        let mut stats = Stats::new();
+        stats.record(&stats_frame(17, true, false));
+        stats.record(&stats_frame(17, true, false));
+        stats.record(&stats_frame(11, true, false));
+        assert_eq!(stats.frames_for_df(17), 2);
+        assert_eq!(stats.frames_for_df(11), 1);
+        assert_eq!(stats.frames_for_df(4), 0);
+    }
+
+    #[test]
+    fn stats_reset_clears_every_counter() {
+        let mut stats = Stats::new();
+        stats.record(&stats_frame(17, true, false));
+        stats.record(&stats_frame(0, false, false));
+        stats.reset();
+        assert_eq!(stats.total_frames(), 0);
+        assert_eq!(stats.valid_frames(), 0);
+        assert_eq!(stats.crc_failures(), 0);
+        assert_eq!(stats.frames_for_df(17), 0);
+    }
+
+    #[test]
+    fn stats_display_summarizes_totals_and_per_df_counts() {
+        let mut stats = Stats::new();
+        stats.record(&stats_frame(17, true, true));
+        stats.record(&stats_frame(0, false, false));
+        assert_eq!(stats.to_string(), "2 frames (1 valid, 1 CRC failures, 1 corrected) DF17=1");
+    }
+
+    fn quality_frame(timestamp: u64, valid: bool, even_cpr: bool, odd_cpr: bool) -> ModesMessage {
+        let mut msg = ModesMessage::default();
+        msg.timestamp = timestamp;
+        msg.valid = valid;
+        msg.even_cpr = even_cpr;
+        msg.odd_cpr = odd_cpr;
+        msg
+    }
+
+    #[test]
+    fn quality_snapshot_is_zeroed_before_anything_is_recorded() {
+        let quality = Quality::with_window(1_000);
+        assert_eq!(quality.snapshot(), QualitySnapshot::default());
+    }
+
+    #[test]
+    fn quality_tracks_message_and_position_rate_over_the_window() {
+        let mut quality = Quality::with_window(TICKS_PER_SECOND as u64);
+        quality.record(&quality_frame(0, true, true, false));
+        quality.record(&quality_frame(TICKS_PER_SECOND as u64 / 2, true, false, false));
+
+        let snapshot = quality.snapshot();
+        assert_eq!(snapshot.message_rate, 4.0);
+        assert_eq!(snapshot.position_rate, 2.0);
+        assert_eq!(snapshot.valid_fraction, 1.0);
+    }
+
+    #[test]
+    fn quality_counts_an_invalid_frame_against_valid_fraction_but_not_position_rate() {
+        let mut quality = Quality::with_window(1_000);
+        quality.record(&quality_frame(0, false, true, false));
+        quality.record(&quality_frame(500, true, false, false));
+
+        let snapshot = quality.snapshot();
+        assert_eq!(snapshot.valid_fraction, 0.5);
+        assert_eq!(snapshot.position_rate, 0.0);
+    }
+
+    #[test]
+    fn quality_evicts_samples_that_age_out_of_the_window() {
+        let mut quality = Quality::with_window(1_000);
+        quality.record(&quality_frame(0, true, true, false));
+        quality.record(&quality_frame(2_000, true, false, false));
+
+        let snapshot = quality.snapshot();
+        assert_eq!(snapshot.position_rate, 0.0);
+    }
+
+    #[test]
+    fn df_description_covers_every_standard_downlink_format() {
+        for df in [0, 4, 5, 11, 16, 17, 18, 19, 20, 21, 24] {
+            assert!(!df_description(df).is_empty(), "DF{} has no description", df);
+        }
+    }
+
+    #[test]
+    fn df_description_covers_every_special_df_constant() {
+        for df in [
+            DF_MODEAC,
+            DF_EVENT_TIMESTAMP_JUMP,
+            DF_EVENT_MODE_CHANGE,
+            DF_EVENT_EPOCH_ROLLOVER,
+            DF_EVENT_RADARCAPE_STATUS,
+            DF_EVENT_RADARCAPE_POSITION,
+        ] {
+            assert!(!df_description(df).is_empty(), "special DF {} has no description", df);
+        }
+    }
+
+    #[test]
+    fn df_description_falls_back_for_an_unrecognized_df() {
+        assert!(!df_description(63).is_empty());
+    }
+}
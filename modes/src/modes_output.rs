@@ -0,0 +1,240 @@
+/*
+ * Part of mlat-client-rust (https://github.com/tjmullicani/mlat-client-rust) - an ADS-B multilateration client.
+ * Based on mlat-client (https://github.com/mutability/mlat-client)
+ * Copyright 2023, Timothy Mullican <timothy.j.mullican@gmail.com>
+ * Copyright 2015, Oliver Jowett <oliver@mutability.co.uk>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * References:
+ *   http://woodair.net/sbs/article/barebones42_socket_data.htm
+ *   https://github.com/MalcolmRobb/dump1090
+ */
+
+use crate::modes_cpr::CprPosition;
+use crate::modes_message::ModesMessage;
+
+/// Converts decoded `ModesMessage`s into a wire format consumed by
+/// downstream tools (virtual radar displays, loggers, ...), rather than
+/// this client's internal debug representations.
+///
+/// `position`, when available, is the CPR-derived fix for this message
+/// (see `modes_cpr::CprDecoder`); encoders that have no use for a fix
+/// (AVR) simply ignore it.
+pub trait FrameEncoder {
+    /// Encodes one message, or returns `None` if this encoder has
+    /// nothing meaningful to emit for it (e.g. an SBS transmission type
+    /// with no equivalent for this message's DF).
+    fn encode(&self, message: &ModesMessage, position: Option<CprPosition>) -> Option<String>;
+}
+
+/// Raw AVR text format: `*<hex>;`, or `@<12-digit-hex-timestamp><hex>;`
+/// when mlat timestamps are wanted.
+pub struct AvrEncoder {
+    pub with_mlat_timestamp: bool,
+}
+
+impl AvrEncoder {
+    pub fn new(with_mlat_timestamp: bool) -> Self {
+        AvrEncoder { with_mlat_timestamp }
+    }
+}
+
+impl FrameEncoder for AvrEncoder {
+    fn encode(&self, message: &ModesMessage, _position: Option<CprPosition>) -> Option<String> {
+        if message.data.is_empty() {
+            return None;
+        }
+
+        let hex: String = message.data.iter().map(|b| format!("{:02X}", b)).collect();
+        if self.with_mlat_timestamp {
+            Some(format!("@{:012X}{};", message.timestamp & 0xFFFFFFFFFFFF, hex))
+        } else {
+            Some(format!("*{};", hex))
+        }
+    }
+}
+
+/// The comma-separated SBS-1 "BaseStation" format used by virtual radar
+/// displays. Only the transmission types this decoder can actually
+/// populate are emitted: MSG,1 (identification, DF17/18 callsigns),
+/// MSG,3 (airborne position, DF17/18 position metypes), MSG,4 (airborne
+/// velocity, DF17/18 ME type 19), and MSG,5 (surveillance altitude,
+/// DF0/4/16/20). Date/time fields are left blank, since this client does
+/// not track wall-clock time for received frames, and Squawk is left
+/// blank too, since `ModesMessage` doesn't carry a decoded Mode-A/C
+/// squawk (that lives on `libbeast::Frame`, decoded from a different,
+/// non-Mode-S message type entirely).
+pub struct SbsEncoder;
+
+impl SbsEncoder {
+    // MSG,<type>,<session>,<aircraft>,<hexident>,<flight>,<date gen>,
+    // <time gen>,<date log>,<time log>,<callsign>,<altitude>,<gspeed>,
+    // <track>,<lat>,<lon>,<vrate>,<squawk>,<alert>,<emergency>,<spi>,
+    // <on ground>
+    //
+    // The trailing `<nuc>` column is not part of the stock SBS format;
+    // it is this client's own extension, carrying the NUCp value used to
+    // derive the fix's containment radius, for consumers that want it.
+    fn line(&self, msgtype: u32, message: &ModesMessage, position: Option<CprPosition>) -> String {
+        let callsign = message.callsign.as_deref().unwrap_or("");
+        let altitude = if message.valid && message.altitude != 0 { message.altitude.to_string() } else { String::new() };
+        let ground_speed = message.ground_speed.map(|s| s.to_string()).unwrap_or_default();
+        let track = message.track.map(|t| format!("{:.0}", t)).unwrap_or_default();
+        let vertical_rate = message.vertical_rate.map(|v| v.to_string()).unwrap_or_default();
+        let (lat, lon) = match position {
+            Some(p) => (format!("{:.5}", p.lat), format!("{:.5}", p.lon)),
+            None => (String::new(), String::new()),
+        };
+
+        format!(
+            "MSG,{},1,1,{:06X},1,,,,,{},{},{},{},{},{},{},,,,,,{}",
+            msgtype, message.address, callsign, altitude, ground_speed, track, lat, lon, vertical_rate, message.nuc
+        )
+    }
+}
+
+impl FrameEncoder for SbsEncoder {
+    fn encode(&self, message: &ModesMessage, position: Option<CprPosition>) -> Option<String> {
+        if !message.valid {
+            return None;
+        }
+
+        match message.df {
+            17 | 18 if message.callsign.is_some() => Some(self.line(1, message, None)),
+            17 | 18 if message.even_cpr || message.odd_cpr => Some(self.line(3, message, position)),
+            17 | 18 if message.ground_speed.is_some() || message.vertical_rate.is_some() => {
+                Some(self.line(4, message, None))
+            }
+            0 | 4 | 16 | 20 => Some(self.line(5, message, None)),
+            _ => None,
+        }
+    }
+}
+
+/// Line-delimited JSON, for piping into `jq` or logging to a file: one
+/// decoded message per line, every transmission type at once (unlike
+/// `SbsEncoder`, which only emits a line for DFs that map onto one of the
+/// handful of SBS transmission types). Optional fields that `decode()`
+/// didn't populate for this message are left out of the object entirely
+/// rather than written as `null`, so e.g. `jq 'select(.callsign)'` works
+/// without an extra `and .callsign != null`.
+pub struct JsonEncoder;
+
+impl FrameEncoder for JsonEncoder {
+    fn encode(&self, message: &ModesMessage, position: Option<CprPosition>) -> Option<String> {
+        let mut json = format!(r#"{{"timestamp":{},"df":{},"valid":{}"#, message.timestamp, message.df, message.valid);
+
+        let icao = message.icao_hex();
+        if !icao.is_empty() {
+            json.push_str(&format!(r#","icao":"{}""#, icao));
+        }
+        if message.valid && message.altitude != 0 {
+            json.push_str(&format!(r#","altitude":{}"#, message.altitude));
+        }
+        if let Some(p) = position {
+            json.push_str(&format!(r#","lat":{},"lon":{}"#, p.lat, p.lon));
+        }
+        if let Some(ref callsign) = message.callsign {
+            json.push_str(&format!(r#","callsign":"{}""#, callsign));
+        }
+        if let Some(speed) = message.ground_speed {
+            json.push_str(&format!(r#","ground_speed":{}"#, speed));
+        }
+        if let Some(track) = message.track {
+            json.push_str(&format!(r#","track":{}"#, track));
+        }
+        if let Some(rate) = message.vertical_rate {
+            json.push_str(&format!(r#","vertical_rate":{}"#, rate));
+        }
+        if let Some(squawk) = message.squawk {
+            json.push_str(&format!(r#","squawk":"{:04}""#, squawk));
+        }
+        json.push('}');
+
+        Some(json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid_df17_message() -> ModesMessage {
+        let mut message = ModesMessage::default();
+        message.valid = true;
+        message.df = 17;
+        message.timestamp = 100;
+        message.address = 0xABCDEF;
+        message
+    }
+
+    #[test]
+    fn json_encoder_omits_absent_optional_fields() {
+        let message = ModesMessage::default();
+        let json = JsonEncoder.encode(&message, None).unwrap();
+
+        assert!(!json.contains("icao"));
+        assert!(!json.contains("altitude"));
+        assert!(!json.contains("lat"));
+        assert!(!json.contains("callsign"));
+        assert!(!json.contains("ground_speed"));
+        assert!(!json.contains("track"));
+        assert!(!json.contains("vertical_rate"));
+        assert!(!json.contains("squawk"));
+    }
+
+    #[test]
+    fn json_encoder_includes_icao_and_altitude_for_a_valid_message() {
+        let mut message = valid_df17_message();
+        message.altitude = 35000;
+        let json = JsonEncoder.encode(&message, None).unwrap();
+
+        assert!(json.contains(r#""icao":"ABCDEF""#));
+        assert!(json.contains(r#""altitude":35000"#));
+    }
+
+    #[test]
+    fn json_encoder_includes_position_when_given_one() {
+        let message = valid_df17_message();
+        let json = JsonEncoder.encode(&message, Some(CprPosition { lat: 51.5, lon: -0.1, nuc: 7 })).unwrap();
+
+        assert!(json.contains(r#""lat":51.5"#));
+        assert!(json.contains(r#""lon":-0.1"#));
+    }
+
+    #[test]
+    fn json_encoder_includes_callsign_and_squawk_when_present() {
+        let mut message = valid_df17_message();
+        message.callsign = Some("UAL123".to_string());
+        message.squawk = Some(1200);
+        let json = JsonEncoder.encode(&message, None).unwrap();
+
+        assert!(json.contains(r#""callsign":"UAL123""#));
+        assert!(json.contains(r#""squawk":"1200""#));
+    }
+
+    #[test]
+    fn json_encoder_always_encodes_something_unlike_sbs_encoder() {
+        // DF11, no position/callsign/velocity: SbsEncoder has nothing to
+        // say about this, but JsonEncoder should still emit a line.
+        let mut message = ModesMessage::default();
+        message.valid = true;
+        message.df = 11;
+        message.address = 0x4840D6;
+
+        assert!(SbsEncoder.encode(&message, None).is_none());
+        assert!(JsonEncoder.encode(&message, None).is_some());
+    }
+}
@@ -0,0 +1,179 @@
+/*
+ * Part of mlat-client-rust (https://github.com/tjmullicani/mlat-client-rust) - an ADS-B multilateration client.
+ * Based on mlat-client (https://github.com/mutability/mlat-client)
+ * Copyright 2023, Timothy Mullican <timothy.j.mullican@gmail.com>
+ * Copyright 2015, Oliver Jowett <oliver@mutability.co.uk>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! An ICAO address allow/block list for `--address-filter`, checked in the
+//! AVR input pipeline once `decode()` has populated `ModesMessage::address`.
+//! Building the filter is separate from parsing its spec on the CLI
+//! (`main::parse_address_filter_spec` only checks the entries are
+//! well-formed) so a bad `@file` path fails with a clear I/O error at
+//! startup, once logging is set up, rather than inside clap's own error
+//! path.
+
+use std::collections::HashSet;
+use std::fs;
+
+/// An allow/block list of ICAO addresses. An empty `allow` set means "no
+/// allowlist" (everything not blocked passes); a non-empty one means only
+/// those addresses pass. `block` always wins over `allow`.
+#[derive(Debug, Clone, Default)]
+pub struct AddressFilter {
+    allow: HashSet<i32>,
+    block: HashSet<i32>,
+}
+
+impl AddressFilter {
+    /// True if `address` should be processed: not on the block list, and
+    /// either the allow list is empty or `address` is on it.
+    pub fn permits(&self, address: i32) -> bool {
+        if self.block.contains(&address) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.contains(&address)
+    }
+
+    /// Parses a `--address-filter` spec into an `AddressFilter`. The spec
+    /// is a comma-separated list of entries:
+    ///   - `4840D6` -- adds the ICAO address to the allow list
+    ///   - `-4840D6` -- adds the ICAO address to the block list
+    ///   - `@path` -- reads more entries (one per line, `#`-comments and
+    ///     blank lines ignored, same `addr`/`-addr` syntax) from a file
+    ///
+    /// Returns an error naming the offending entry for a malformed hex
+    /// address, or the `io::Error` for a file that can't be read.
+    pub fn from_spec(spec: &str) -> Result<Self, String> {
+        let mut filter = AddressFilter::default();
+        for entry in spec.split(',') {
+            filter.apply_entry(entry.trim())?;
+        }
+        Ok(filter)
+    }
+
+    fn apply_entry(&mut self, entry: &str) -> Result<(), String> {
+        if entry.is_empty() {
+            return Ok(());
+        }
+        if let Some(path) = entry.strip_prefix('@') {
+            let contents = fs::read_to_string(path).map_err(|e| format!("could not read address list `{}`: {}", path, e))?;
+            for line in contents.lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                self.apply_entry(line)?;
+            }
+            return Ok(());
+        }
+        if let Some(hex) = entry.strip_prefix('-') {
+            self.block.insert(parse_icao_hex(hex)?);
+        } else {
+            self.allow.insert(parse_icao_hex(entry)?);
+        }
+        Ok(())
+    }
+}
+
+// Parses a bare ICAO address (1-6 hex digits, no leading "0x" or trailing
+// junk) into the same `i32` representation `ModesMessage::address` uses.
+fn parse_icao_hex(s: &str) -> Result<i32, String> {
+    if s.is_empty() || s.len() > 6 || !s.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("`{}` is not a valid ICAO address (expected 1-6 hex digits)", s));
+    }
+    i32::from_str_radix(s, 16).map_err(|_| format!("`{}` is not a valid ICAO address", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    // A scratch file under the OS temp dir, removed on drop, so tests
+    // don't need an external tempfile crate for a single throwaway file.
+    struct ScratchFile(PathBuf);
+
+    impl ScratchFile {
+        fn new(name: &str) -> Self {
+            let mut path = std::env::temp_dir();
+            path.push(format!("mlat-client-rust-test-{}-{}", std::process::id(), name));
+            ScratchFile(path)
+        }
+    }
+
+    impl Drop for ScratchFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn permits_everything_when_the_filter_is_empty() {
+        let filter = AddressFilter::default();
+        assert!(filter.permits(0xabcdef));
+    }
+
+    #[test]
+    fn from_spec_restricts_to_the_allow_list_when_one_is_given() {
+        let filter = AddressFilter::from_spec("ABCDEF,123456").unwrap();
+        assert!(filter.permits(0xabcdef));
+        assert!(filter.permits(0x123456));
+        assert!(!filter.permits(0x000001));
+    }
+
+    #[test]
+    fn from_spec_blocks_addresses_prefixed_with_a_dash() {
+        let filter = AddressFilter::from_spec("-ABCDEF").unwrap();
+        assert!(!filter.permits(0xabcdef));
+        assert!(filter.permits(0x123456)); // no allowlist, so everything else still passes
+    }
+
+    #[test]
+    fn block_wins_over_allow_for_the_same_address() {
+        let filter = AddressFilter::from_spec("ABCDEF,-ABCDEF").unwrap();
+        assert!(!filter.permits(0xabcdef));
+    }
+
+    #[test]
+    fn from_spec_rejects_a_malformed_hex_entry() {
+        assert!(AddressFilter::from_spec("NOTHEX").is_err());
+        assert!(AddressFilter::from_spec("1234567").is_err()); // too many digits
+    }
+
+    #[test]
+    fn from_spec_reads_entries_from_a_file() {
+        let scratch = ScratchFile::new("address-list.txt");
+        let mut file = fs::File::create(&scratch.0).unwrap();
+        writeln!(file, "# comment").unwrap();
+        writeln!(file, "ABCDEF").unwrap();
+        writeln!(file, "-123456").unwrap();
+        writeln!(file).unwrap();
+        drop(file);
+
+        let spec = format!("@{}", scratch.0.display());
+        let filter = AddressFilter::from_spec(&spec).unwrap();
+        assert!(filter.permits(0xabcdef));
+        assert!(!filter.permits(0x123456));
+    }
+
+    #[test]
+    fn from_spec_reports_a_missing_file_clearly() {
+        let result = AddressFilter::from_spec("@/no/such/file/here.txt");
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,621 @@
+/*
+ * Part of mlat-client-rust (https://github.com/tjmullicani/mlat-client-rust) - an ADS-B multilateration client.
+ * Based on mlat-client (https://github.com/mutability/mlat-client)
+ * Copyright 2023, Timothy Mullican <timothy.j.mullican@gmail.com>
+ * Copyright 2015, Oliver Jowett <oliver@mutability.co.uk>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{debug, info, warn};
+
+use modes::modes_message::ModesMessage;
+
+use crate::reconnect::connect_with_backoff;
+use crate::Cli;
+
+// How often the receive loop wakes up to check `shutdown`, when there is
+// no data to read in the meantime.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(30);
+
+// mlat-server identifies the receiver's timestamp source by this string
+// so it knows how to interpret sync message timestamps; this client only
+// speaks the Beast 12MHz clock (see `libbeast::TimestampFormat::Mhz12`),
+// so it is the only value ever sent.
+const CLOCK_TYPE: &str = "dump1090";
+
+/// Connects to `cfg.server`, performs the mlat-client handshake, opens a
+/// UDP transport for sync/mlat messages if the server offers one and
+/// `--no-udp` was not given, and blocks reading and logging whatever the
+/// server sends back over TCP until `shutdown` is set (by the Ctrl-C
+/// handler in `main`). If the connection drops or can't be established,
+/// reconnects with exponential backoff via `connect_with_backoff` rather
+/// than giving up, so a network blip or a server restart doesn't kill the
+/// feeder. It does not yet feed anything into the multilateration
+/// protocol proper.
+pub fn run(cfg: &Cli, shutdown: Arc<AtomicBool>) -> io::Result<()> {
+    let addr = server_addr_string(cfg);
+    while !shutdown.load(Ordering::SeqCst) {
+        let stream = connect_with_backoff(&addr, MAX_RECONNECT_BACKOFF);
+        if let Err(e) = run_with_stream(cfg, stream, Arc::clone(&shutdown)) {
+            warn!("connection to {} lost: {}", addr, e);
+        }
+    }
+
+    Ok(())
+}
+
+// Formats `cfg.server` back into the `host:port`/`[host]:port` string
+// `connect_with_backoff` (and `TcpStream::connect`) expect, bracketing the
+// host if it looks like an IPv6 address (contains a `:`) so it isn't
+// confused with the port separator.
+fn server_addr_string(cfg: &Cli) -> String {
+    let (host, port) = &cfg.server;
+    if host.contains(':') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
+fn run_with_stream(cfg: &Cli, mut stream: TcpStream, shutdown: Arc<AtomicBool>) -> io::Result<()> {
+    let handshake = build_handshake(cfg);
+    debug!("sending handshake: {}", handshake);
+    stream.write_all(handshake.as_bytes())?;
+    stream.write_all(b"\n")?;
+
+    // A finite read timeout, rather than an unbounded blocking read, is
+    // what lets the loop below notice `shutdown` in a timely manner
+    // instead of only after the server sends its next line.
+    stream.set_read_timeout(Some(SHUTDOWN_POLL_INTERVAL))?;
+
+    let mut reader = BufReader::new(stream);
+
+    let mut reply = String::new();
+    reader.read_line(&mut reply)?;
+    match parse_server_settings(&reply) {
+        Ok(settings) => {
+            if let Some(motd) = &settings.motd {
+                info!("server motd: {}", motd);
+            }
+            info!("negotiated settings: {:?}", settings);
+
+            match select_transport(cfg, &settings) {
+                Transport::Udp { key, addr } => match open_udp_transport(addr, &key) {
+                    Ok(_) => info!("using UDP transport at {} for sync/mlat messages", addr),
+                    Err(e) => warn!("could not open UDP transport to {} ({}), staying on TCP", addr, e),
+                },
+                Transport::Tcp => debug!("using TCP for sync/mlat messages"),
+            }
+        }
+        Err(e) => warn!("could not parse handshake reply ({}): {}", e, reply.trim_end()),
+    }
+
+    while !shutdown.load(Ordering::SeqCst) {
+        let mut line = String::new();
+        match reader.read_line(&mut line) {
+            Ok(0) => {
+                info!("server closed the connection");
+                return Ok(());
+            }
+            Ok(_) => info!("server: {}", line.trim_end()),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut => continue,
+            Err(e) => return Err(e),
+        }
+    }
+
+    info!("shutting down");
+    Ok(())
+}
+
+/// Builds the JSON handshake message the mlat-server protocol expects:
+/// client version, receiver location, the operator's contact `user`
+/// string, the receiver's clock type, and the compression schemes this
+/// client can decode server responses in.
+pub fn build_handshake(cfg: &Cli) -> String {
+    format!(
+        concat!(
+            r#"{{"version":3,"user":"{}","lat":{},"lon":{},"alt":{},"#,
+            r#""clock_type":"{}","compress":["zlib","none"],"privacy":{}}}"#,
+        ),
+        json_escape(&cfg.user),
+        cfg.lat,
+        cfg.lon,
+        cfg.alt,
+        CLOCK_TYPE,
+        cfg.privacy,
+    )
+}
+
+fn json_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// The single-byte message-type tag mlat-server expects at the start of a
+// sync message.
+const SYNC_MESSAGE_TYPE: u8 = b'S';
+
+/// Builds a "sync" message: the core mlat wire format for reporting a
+/// paired even/odd DF17 (or DF18) airborne-position observation of the
+/// same aircraft, so the server can measure the clock offset between this
+/// receiver and whichever others also saw the pair. Not every candidate
+/// pair is worth sending; callers should only pass frames that are:
+///   - `df == 17` or `df == 18`, with `valid` CRC and no bit-error
+///     correction (`!corrected`), so a miscorrected frame can't poison a
+///     clock sync;
+///   - a strong `signal`, since a marginal reception is more likely to be
+///     a spurious decode than a real one;
+///   - recent (seen within the last second or so of each other), so the
+///     pairing is really "the same even/odd transmission pair" and not
+///     two unrelated broadcasts from the same aircraft.
+///
+/// `msg_a` and `msg_b` must be one even- and one odd-CPR frame from the
+/// same aircraft (`even_cpr`/`odd_cpr` decide which is which; argument
+/// order doesn't matter). The wire format is `'S'`, the 3-byte ICAO
+/// address, then the even frame's 6-byte (48-bit) timestamp and 1-byte
+/// signal level, then the same two fields for the odd frame.
+pub fn build_sync_message(msg_a: &ModesMessage, msg_b: &ModesMessage) -> Vec<u8> {
+    let (even, odd) = if msg_a.even_cpr { (msg_a, msg_b) } else { (msg_b, msg_a) };
+
+    let mut out = Vec::with_capacity(1 + 3 + 6 + 1 + 6 + 1);
+    out.push(SYNC_MESSAGE_TYPE);
+    out.extend_from_slice(&(even.address as u32).to_be_bytes()[1..]);
+    out.extend_from_slice(&be48_timestamp(even.timestamp));
+    out.push(even.signal);
+    out.extend_from_slice(&be48_timestamp(odd.timestamp));
+    out.push(odd.signal);
+    out
+}
+
+// Encodes the low 48 bits of a 12MHz tick count as 6 big-endian bytes,
+// matching the wire width of the receiver's clock counter (see
+// `modes::modes_message::TIMESTAMP_EPOCH_TICKS`).
+fn be48_timestamp(ticks: u64) -> [u8; 6] {
+    ticks.to_be_bytes()[2..].try_into().unwrap()
+}
+
+/// Settings negotiated with the server: the parsed reply to
+/// `build_handshake`'s JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerSettings {
+    pub compress: String,
+    pub return_results: bool,
+    pub motd: Option<String>,
+    pub udp_transport: Option<UdpTransportInfo>,
+    // The ICAO addresses the server is asking this receiver to report on,
+    // if it sent one. `None` means the server hasn't told us to filter by
+    // address (yet), so `should_forward` doesn't filter on it either.
+    pub interesting_addresses: Option<Vec<i32>>,
+}
+
+/// The UDP endpoint and per-connection key the server offers for
+/// sync/mlat messages, if it offers UDP at all.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UdpTransportInfo {
+    pub port: u16,
+    pub key: String,
+}
+
+/// The transport used to send time-sensitive sync/mlat messages once the
+/// handshake has completed. TCP is always available, since it's how the
+/// handshake itself was sent; UDP is preferred when the server offers it
+/// and `--no-udp` was not given, since it avoids TCP's head-of-line
+/// blocking for latency-sensitive messages.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transport {
+    Tcp,
+    Udp { key: String, addr: SocketAddr },
+}
+
+/// Picks a `Transport` for sync/mlat messages, honoring `--no-udp` and
+/// falling back to `Tcp` if the server didn't offer a `udp_transport` or
+/// its offered address can't be resolved.
+fn select_transport(cfg: &Cli, settings: &ServerSettings) -> Transport {
+    if cfg.no_udp {
+        debug!("--no-udp given, staying on TCP for sync/mlat messages");
+        return Transport::Tcp;
+    }
+
+    let Some(udp) = &settings.udp_transport else {
+        debug!("server did not offer a udp_transport, staying on TCP for sync/mlat messages");
+        return Transport::Tcp;
+    };
+
+    let host = cfg.server.0.as_str();
+    match (host, udp.port).to_socket_addrs().ok().and_then(|mut addrs| addrs.next()) {
+        Some(addr) => Transport::Udp { key: udp.key.clone(), addr },
+        None => {
+            warn!("could not resolve {}:{} for UDP, staying on TCP", host, udp.port);
+            Transport::Tcp
+        }
+    }
+}
+
+/// Opens a UDP socket to `addr` and sends the server-provided `key` as
+/// an initial datagram, so the server can associate this socket's source
+/// address with the connection before any real sync/mlat traffic arrives.
+fn open_udp_transport(addr: SocketAddr, key: &str) -> io::Result<UdpSocket> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.connect(addr)?;
+    socket.send(key.as_bytes())?;
+    Ok(socket)
+}
+
+/// Parses the server's handshake reply into a `ServerSettings`. This
+/// workspace has no JSON dependency, so rather than a general-purpose
+/// parser this just scans for the handful of top-level (and, for
+/// `udp_transport`, one level nested) fields this client understands.
+pub fn parse_server_settings(reply: &str) -> Result<ServerSettings, String> {
+    let compress = json_string_field(reply, "compress")
+        .ok_or_else(|| "handshake reply is missing \"compress\"".to_string())?;
+    let return_results = json_bool_field(reply, "return_results").unwrap_or(true);
+    let motd = json_string_field(reply, "motd");
+    let udp_transport = json_object_field(reply, "udp_transport").and_then(|obj| {
+        let port = json_u16_field(&obj, "port")?;
+        let key = json_string_field(&obj, "key")?;
+        Some(UdpTransportInfo { port, key })
+    });
+    let interesting_addresses = json_i32_array_field(reply, "interesting_addresses");
+
+    Ok(ServerSettings { compress, return_results, motd, udp_transport, interesting_addresses })
+}
+
+// Finds `key`'s value in `json`, returning the byte offset of the first
+// non-whitespace character after its `:`.
+fn json_field_start(json: &str, key: &str) -> Option<usize> {
+    let needle = format!("\"{}\"", key);
+    let key_pos = json.find(&needle)?;
+    let after_key = &json[key_pos + needle.len()..];
+    let colon = after_key.find(':')?;
+    let value_start = key_pos + needle.len() + colon + 1;
+    Some(value_start + json[value_start..].len() - json[value_start..].trim_start().len())
+}
+
+fn json_string_field(json: &str, key: &str) -> Option<String> {
+    let start = json_field_start(json, key)?;
+    let rest = json[start..].strip_prefix('"')?;
+    let end = rest.find('"')?;
+    Some(rest[..end].to_string())
+}
+
+fn json_bool_field(json: &str, key: &str) -> Option<bool> {
+    let start = json_field_start(json, key)?;
+    let rest = &json[start..];
+    if rest.starts_with("true") {
+        Some(true)
+    } else if rest.starts_with("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn json_u16_field(json: &str, key: &str) -> Option<u16> {
+    let start = json_field_start(json, key)?;
+    let rest = &json[start..];
+    let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    rest[..end].parse().ok()
+}
+
+// Returns the `{...}` substring (braces included) of a nested object
+// field. Assumes that object has no further nested braces of its own,
+// which holds for every object this protocol currently sends.
+fn json_object_field(json: &str, key: &str) -> Option<String> {
+    let start = json_field_start(json, key)?;
+    let rest = json[start..].strip_prefix('{')?;
+    let end = rest.find('}')?;
+    Some(format!("{{{}}}", &rest[..end]))
+}
+
+// Parses a `[1,2,3]`-style array of (possibly negative) integers. Assumes
+// the array has no nested brackets, which holds for every array this
+// protocol currently sends.
+fn json_i32_array_field(json: &str, key: &str) -> Option<Vec<i32>> {
+    let start = json_field_start(json, key)?;
+    let rest = json[start..].strip_prefix('[')?;
+    let end = rest.find(']')?;
+    let body = rest[..end].trim();
+    if body.is_empty() {
+        return Some(Vec::new());
+    }
+    body.split(',').map(|entry| entry.trim().parse().ok()).collect()
+}
+
+// DFs the mlat-server wants forwarded, per the mlat-client protocol:
+// surveillance replies (0,4,5,16,20,21) and Mode S extended squitter
+// (11,17,18). Anything else isn't useful to a solve and is dropped rather
+// than sent uplink.
+const FORWARDABLE_DFS: [u32; 9] = [0, 4, 5, 11, 16, 17, 18, 20, 21];
+
+/// True if `msg` is worth forwarding to the mlat-server: it decoded
+/// cleanly (`valid`, i.e. its CRC checked out), its Downlink Format is one
+/// of `FORWARDABLE_DFS`, it isn't a relayed TIS-B/ADS-R track (`is_tisb`/
+/// `is_adsr`, DF18 only -- those are a ground station's report of someone
+/// else's position, not that aircraft's own transmission, so they can't be
+/// used to sync this receiver's clock), and, if the server gave us an
+/// `interesting_addresses` list, `msg.address` is on it. Applying this
+/// before every uplinked message keeps the connection from being flooded
+/// with frames the server would just discard anyway.
+pub fn should_forward(msg: &ModesMessage, settings: &ServerSettings) -> bool {
+    msg.valid
+        && FORWARDABLE_DFS.contains(&msg.df)
+        && !msg.is_tisb
+        && !msg.is_adsr
+        && settings
+            .interesting_addresses
+            .as_ref()
+            .map(|addresses| addresses.contains(&msg.address))
+            .unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_cli() -> Cli {
+        Cli {
+            lat: 51.47,
+            lon: -0.4543,
+            alt: 25.0,
+            privacy: false,
+            user: "test@example.com".to_string(),
+            server: ("mlat.example.com".to_string(), 31090),
+            no_udp: false,
+            input: None,
+            input_file: None,
+            input_format: "beast".to_string(),
+            replay_realtime: false,
+            record_file: None,
+            output: None,
+            stats_interval: 60,
+            max_messages: None,
+            max_range_km: modes::modes_cpr::DEFAULT_MAX_RANGE_KM,
+            filter_df: None,
+            address_filter: None,
+            no_crc_check: false,
+            log_level: None,
+            verbose: 0,
+            quiet: 0,
+            log_style: "auto".to_string(),
+        }
+    }
+
+    #[test]
+    fn build_handshake_includes_the_receiver_location_and_user() {
+        let handshake = build_handshake(&test_cli());
+
+        assert_eq!(json_string_field(&handshake, "user").as_deref(), Some("test@example.com"));
+        assert!(handshake.contains("\"lat\":51.47"));
+        assert!(handshake.contains("\"lon\":-0.4543"));
+        assert!(handshake.contains("\"alt\":25"));
+        assert!(handshake.contains(&format!("\"clock_type\":\"{}\"", CLOCK_TYPE)));
+        assert!(handshake.contains("\"privacy\":false"));
+    }
+
+    #[test]
+    fn build_handshake_escapes_quotes_in_the_user_string() {
+        let mut cli = test_cli();
+        cli.user = "quote\"inside".to_string();
+
+        let handshake = build_handshake(&cli);
+
+        assert_eq!(json_string_field(&handshake, "user").as_deref(), Some("quote\\\"inside"));
+    }
+
+    #[test]
+    fn parse_server_settings_reads_top_level_fields() {
+        let reply = r#"{"compress":"zlib","return_results":false,"motd":"welcome"}"#;
+
+        let settings = parse_server_settings(reply).expect("valid reply parses");
+
+        assert_eq!(settings.compress, "zlib");
+        assert!(!settings.return_results);
+        assert_eq!(settings.motd.as_deref(), Some("welcome"));
+        assert_eq!(settings.udp_transport, None);
+        assert_eq!(settings.interesting_addresses, None);
+    }
+
+    #[test]
+    fn parse_server_settings_reads_the_nested_udp_transport_offer() {
+        let reply = r#"{"compress":"none","udp_transport":{"port":31090,"key":"abc123"}}"#;
+
+        let settings = parse_server_settings(reply).expect("valid reply parses");
+
+        let udp = settings.udp_transport.expect("server offered udp_transport");
+        assert_eq!(udp.port, 31090);
+        assert_eq!(udp.key, "abc123");
+    }
+
+    #[test]
+    fn parse_server_settings_reads_the_interesting_addresses_list() {
+        let reply = r#"{"compress":"none","interesting_addresses":[11259375,-1,0]}"#;
+
+        let settings = parse_server_settings(reply).expect("valid reply parses");
+
+        assert_eq!(settings.interesting_addresses, Some(vec![11259375, -1, 0]));
+    }
+
+    #[test]
+    fn parse_server_settings_rejects_a_reply_with_no_compress_field() {
+        assert!(parse_server_settings("{}").is_err());
+    }
+
+    #[test]
+    fn select_transport_uses_udp_when_offered_and_not_disabled() {
+        let mut cli = test_cli();
+        cli.server = ("127.0.0.1".to_string(), 31090);
+        let settings = ServerSettings {
+            compress: "none".to_string(),
+            return_results: true,
+            motd: None,
+            udp_transport: Some(UdpTransportInfo { port: 31091, key: "abc123".to_string() }),
+            interesting_addresses: None,
+        };
+
+        match select_transport(&cli, &settings) {
+            Transport::Udp { key, addr } => {
+                assert_eq!(key, "abc123");
+                assert_eq!(addr.port(), 31091);
+            }
+            Transport::Tcp => panic!("expected UDP transport to be selected"),
+        }
+    }
+
+    #[test]
+    fn select_transport_stays_on_tcp_when_no_udp_is_set() {
+        let mut cli = test_cli();
+        cli.no_udp = true;
+        let settings = ServerSettings {
+            compress: "none".to_string(),
+            return_results: true,
+            motd: None,
+            udp_transport: Some(UdpTransportInfo { port: 31091, key: "abc123".to_string() }),
+            interesting_addresses: None,
+        };
+
+        assert_eq!(select_transport(&cli, &settings), Transport::Tcp);
+    }
+
+    #[test]
+    fn select_transport_stays_on_tcp_when_the_server_does_not_offer_udp() {
+        let settings = ServerSettings {
+            compress: "none".to_string(),
+            return_results: true,
+            motd: None,
+            udp_transport: None,
+            interesting_addresses: None,
+        };
+
+        assert_eq!(select_transport(&test_cli(), &settings), Transport::Tcp);
+    }
+
+    #[test]
+    fn server_addr_string_formats_a_plain_host_and_port() {
+        let mut cli = test_cli();
+        cli.server = ("mlat.example.com".to_string(), 31090);
+        assert_eq!(server_addr_string(&cli), "mlat.example.com:31090");
+    }
+
+    #[test]
+    fn server_addr_string_brackets_an_ipv6_host() {
+        let mut cli = test_cli();
+        cli.server = ("::1".to_string(), 31090);
+        assert_eq!(server_addr_string(&cli), "[::1]:31090");
+    }
+
+    fn sync_candidate(address: i32, even_cpr: bool, timestamp: u64, signal: u8) -> ModesMessage {
+        let mut msg = ModesMessage::default();
+        msg.df = 17;
+        msg.valid = true;
+        msg.address = address;
+        msg.even_cpr = even_cpr;
+        msg.odd_cpr = !even_cpr;
+        msg.timestamp = timestamp;
+        msg.signal = signal;
+        msg
+    }
+
+    #[test]
+    fn build_sync_message_starts_with_the_type_tag_and_icao_address() {
+        let even = sync_candidate(0xabcdef, true, 1_000, 200);
+        let odd = sync_candidate(0xabcdef, false, 2_000, 210);
+
+        let message = build_sync_message(&even, &odd);
+
+        assert_eq!(message[0], b'S');
+        assert_eq!(&message[1..4], &[0xab, 0xcd, 0xef]);
+    }
+
+    #[test]
+    fn build_sync_message_orders_the_even_frame_before_the_odd_frame_regardless_of_argument_order() {
+        let even = sync_candidate(0xabcdef, true, 1_000, 200);
+        let odd = sync_candidate(0xabcdef, false, 2_000, 210);
+
+        let a_then_b = build_sync_message(&even, &odd);
+        let b_then_a = build_sync_message(&odd, &even);
+
+        assert_eq!(a_then_b, b_then_a);
+        // even timestamp (1_000), then even signal (200), then odd timestamp (2_000), then odd signal (210)
+        assert_eq!(&a_then_b[4..10], &[0, 0, 0, 0, 3, 232]);
+        assert_eq!(a_then_b[10], 200);
+        assert_eq!(&a_then_b[11..17], &[0, 0, 0, 0, 7, 208]);
+        assert_eq!(a_then_b[17], 210);
+    }
+
+    fn settings_with_addresses(interesting_addresses: Option<Vec<i32>>) -> ServerSettings {
+        ServerSettings {
+            compress: "none".to_string(),
+            return_results: true,
+            motd: None,
+            udp_transport: None,
+            interesting_addresses,
+        }
+    }
+
+    #[test]
+    fn should_forward_accepts_a_clean_forwardable_df_when_no_address_list_is_set() {
+        let msg = sync_candidate(0xabcdef, true, 1_000, 200);
+        assert!(should_forward(&msg, &settings_with_addresses(None)));
+    }
+
+    #[test]
+    fn should_forward_rejects_a_frame_that_failed_crc() {
+        let mut msg = sync_candidate(0xabcdef, true, 1_000, 200);
+        msg.valid = false;
+        assert!(!should_forward(&msg, &settings_with_addresses(None)));
+    }
+
+    #[test]
+    fn should_forward_rejects_a_df_the_server_never_wants() {
+        let mut msg = sync_candidate(0xabcdef, true, 1_000, 200);
+        msg.df = 19;
+        assert!(!should_forward(&msg, &settings_with_addresses(None)));
+    }
+
+    #[test]
+    fn should_forward_rejects_an_address_not_on_the_interesting_list() {
+        let msg = sync_candidate(0xabcdef, true, 1_000, 200);
+        assert!(!should_forward(&msg, &settings_with_addresses(Some(vec![0x123456]))));
+    }
+
+    #[test]
+    fn should_forward_accepts_an_address_on_the_interesting_list() {
+        let msg = sync_candidate(0xabcdef, true, 1_000, 200);
+        assert!(should_forward(&msg, &settings_with_addresses(Some(vec![0xabcdef]))));
+    }
+
+    #[test]
+    fn should_forward_rejects_a_tisb_relayed_track() {
+        let mut msg = sync_candidate(0xabcdef, true, 1_000, 200);
+        msg.df = 18;
+        msg.is_tisb = true;
+        assert!(!should_forward(&msg, &settings_with_addresses(None)));
+    }
+
+    #[test]
+    fn should_forward_rejects_an_adsr_rebroadcast_track() {
+        let mut msg = sync_candidate(0xabcdef, true, 1_000, 200);
+        msg.df = 18;
+        msg.is_adsr = true;
+        assert!(!should_forward(&msg, &settings_with_addresses(None)));
+    }
+}
@@ -0,0 +1,133 @@
+/*
+ * Part of mlat-client-rust (https://github.com/tjmullicani/mlat-client-rust) - an ADS-B multilateration client.
+ * Based on mlat-client (https://github.com/mutability/mlat-client)
+ * Copyright 2023, Timothy Mullican <timothy.j.mullican@gmail.com>
+ * Copyright 2015, Oliver Jowett <oliver@mutability.co.uk>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Uplink message compression, gated on the `compress` setting negotiated
+//! during the handshake (see `client::build_handshake`/
+//! `client::ServerSettings::compress`). Only `zlib` needs framing of its
+//! own; `none` sends messages as-is, so this module's only real surface
+//! is the `zlib` writer.
+
+use std::io::{self, Write};
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+/// How uplink messages are framed, per the negotiated `compress` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionMode {
+    None,
+    Zlib,
+}
+
+/// Picks a `CompressionMode` from the server's negotiated `compress`
+/// string. Anything this client doesn't recognize (a future `zlib2`, or a
+/// server bug) falls back to `None` rather than failing the connection --
+/// sending uncompressed is always safe, it's just wasted bandwidth.
+pub fn negotiated_mode(compress: &str) -> CompressionMode {
+    match compress {
+        "zlib" => CompressionMode::Zlib,
+        _ => CompressionMode::None,
+    }
+}
+
+/// A `zlib`-mode uplink stream: one long-lived deflate context, flushed
+/// after every message so each `write_frame` call produces a block the
+/// server can decompress as soon as it arrives, while still sharing a
+/// compression dictionary across messages -- unlike compressing each
+/// message independently, which would forfeit most of the benefit of
+/// turning compression on in the first place.
+pub struct ZlibWriter {
+    encoder: ZlibEncoder<Vec<u8>>,
+}
+
+impl ZlibWriter {
+    pub fn new() -> Self {
+        ZlibWriter { encoder: ZlibEncoder::new(Vec::new(), Compression::default()) }
+    }
+
+    /// Compresses `message` into the running stream, flushes it, and
+    /// returns a frame consisting of a 2-byte big-endian length prefix
+    /// followed by whatever compressed bytes the flush produced -- the
+    /// wire format mlat-server expects once `"compress":"zlib"` has been
+    /// negotiated.
+    pub fn write_frame(&mut self, message: &[u8]) -> io::Result<Vec<u8>> {
+        self.encoder.write_all(message)?;
+        self.encoder.flush()?;
+
+        let block: Vec<u8> = self.encoder.get_mut().drain(..).collect();
+        let len: u16 = block
+            .len()
+            .try_into()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "compressed block too large to frame"))?;
+
+        let mut frame = Vec::with_capacity(2 + block.len());
+        frame.extend_from_slice(&len.to_be_bytes());
+        frame.extend_from_slice(&block);
+        Ok(frame)
+    }
+}
+
+impl Default for ZlibWriter {
+    fn default() -> Self {
+        ZlibWriter::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn negotiated_mode_recognizes_zlib() {
+        assert_eq!(negotiated_mode("zlib"), CompressionMode::Zlib);
+    }
+
+    #[test]
+    fn negotiated_mode_falls_back_to_none_for_anything_else() {
+        assert_eq!(negotiated_mode("none"), CompressionMode::None);
+        assert_eq!(negotiated_mode("zlib2"), CompressionMode::None);
+    }
+
+    #[test]
+    fn zlib_writer_frame_is_length_prefixed_and_round_trips() {
+        let mut writer = ZlibWriter::new();
+        let frame = writer.write_frame(b"hello").expect("compresses");
+
+        let len = u16::from_be_bytes([frame[0], frame[1]]) as usize;
+        assert_eq!(len, frame.len() - 2);
+
+        let mut decoder = flate2::read::ZlibDecoder::new(&frame[2..]);
+        let mut decompressed = Vec::new();
+        decoder.read_to_end(&mut decompressed).expect("decompresses");
+        assert_eq!(decompressed, b"hello");
+    }
+
+    #[test]
+    fn zlib_writer_shares_a_dictionary_across_messages() {
+        let mut writer = ZlibWriter::new();
+        let repeated = [b'x'; 200];
+
+        let first = writer.write_frame(&repeated).expect("compresses");
+        let second = writer.write_frame(&repeated).expect("compresses");
+
+        assert!(second.len() < first.len());
+    }
+}
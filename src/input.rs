@@ -0,0 +1,451 @@
+/*
+ * Part of mlat-client-rust (https://github.com/tjmullicani/mlat-client-rust) - an ADS-B multilateration client.
+ * Based on mlat-client (https://github.com/mutability/mlat-client)
+ * Copyright 2023, Timothy Mullican <timothy.j.mullican@gmail.com>
+ * Copyright 2015, Oliver Jowett <oliver@mutability.co.uk>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use log::{info, warn};
+
+use modes::modes_cpr::CprDecoder;
+use modes::modes_message::{DedupWindow, Quality, Stats, TimestampJumpDetector};
+use modes::modes_output::FrameEncoder;
+
+use crate::address_filter::AddressFilter;
+use crate::output::Broadcaster;
+use crate::reconnect::connect_with_backoff;
+use crate::Cli;
+
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(30);
+
+// How often a read on the input socket times out, so the loop below can
+// notice `shutdown` without waiting forever for the next frame.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// The 12MHz Beast/Radarcape tick rate that frame timestamps are recorded
+// in, used to pace `--replay-realtime`.
+const REPLAY_CLOCK_HZ: f64 = 12_000_000.0;
+
+// How often `TeeReader` flushes `--record-file` to disk while data keeps
+// arriving; it also flushes once more when dropped (e.g. on reconnect or
+// shutdown), so a captured file is never more than this far behind.
+const RECORD_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+// How many *consecutive* all-zero-timestamp Beast frames it takes before
+// `read_beast_frames` warns about it. Some receivers legitimately emit a
+// zero timestamp for their very first message, so a frame or two isn't
+// worth logging; a receiver that never latches its timestamp counter at
+// all (a known firmware issue) will blow past this in well under a
+// second and multilateration can't sync without real timestamps.
+const ZERO_TIMESTAMP_WARN_THRESHOLD: u64 = 50;
+
+/// Tees every byte read from `inner` to `sink` as well as returning it
+/// normally, so a live stream can be captured to a file (`--record-file`)
+/// while still being decoded as usual. A failed write to `sink` doesn't
+/// fail the read -- it just stops that connection's capture, since a full
+/// disk shouldn't take down live decoding.
+struct TeeReader<R> {
+    inner: R,
+    sink: BufWriter<File>,
+    last_flush: Instant,
+}
+
+impl<R: Read> TeeReader<R> {
+    fn new(inner: R, sink: File) -> Self {
+        TeeReader { inner, sink: BufWriter::new(sink), last_flush: Instant::now() }
+    }
+}
+
+impl<R: Read> Read for TeeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n > 0 {
+            let _ = self.sink.write_all(&buf[..n]);
+            if self.last_flush.elapsed() >= RECORD_FLUSH_INTERVAL {
+                let _ = self.sink.flush();
+                self.last_flush = Instant::now();
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl<R> Drop for TeeReader<R> {
+    fn drop(&mut self) {
+        let _ = self.sink.flush();
+    }
+}
+
+/// Either `source` unchanged, or `source` teed to `record_file` (see
+/// `TeeReader`), depending on whether `--record-file` is set. A single
+/// `MaybeTee<R>` type lets `read_beast_frames`/`read_avr_frames` stay
+/// generic over `R` without caring which case applies.
+enum MaybeTee<R> {
+    Plain(R),
+    Recording(TeeReader<R>),
+}
+
+impl<R: Read> Read for MaybeTee<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            MaybeTee::Plain(r) => r.read(buf),
+            MaybeTee::Recording(r) => r.read(buf),
+        }
+    }
+}
+
+// Opens `record_file` in append mode (so a reconnect's capture continues
+// the same file rather than overwriting it) and wraps `source` in a
+// `TeeReader`, or passes `source` through unchanged if `record_file` is
+// `None`. If the file can't be opened, logs a warning and continues
+// without recording rather than failing the whole connection over it.
+fn maybe_tee<R: Read>(source: R, record_file: Option<&str>) -> MaybeTee<R> {
+    let Some(path) = record_file else {
+        return MaybeTee::Plain(source);
+    };
+    match OpenOptions::new().create(true).append(true).open(path) {
+        Ok(sink) => MaybeTee::Recording(TeeReader::new(source, sink)),
+        Err(e) => {
+            warn!("could not open --record-file {}: {} (continuing without recording)", path, e);
+            MaybeTee::Plain(source)
+        }
+    }
+}
+
+// State the AVR path keeps across reconnects: unlike `TimestampJumpDetector`
+// (recreated fresh per connection, see `run`), none of this needs resetting
+// when the socket drops, so it's bundled here and threaded through as one
+// argument rather than several.
+struct AvrState {
+    cpr: CprDecoder,
+    dedup: DedupWindow,
+    stats: Stats,
+    quality: Quality,
+    stats_logged_at: Instant,
+    // Total messages decoded so far, for `--max-messages`. Kept separate
+    // from `stats.total_frames()` since that resets every `stats_interval`.
+    processed: u64,
+    address_filter: AddressFilter,
+    // When this `AvrState` was created, used to derive a local monotonic
+    // tick value (in the same 12MHz unit as a real Beast timestamp) for
+    // `*`-prefixed AVR lines, which carry no timestamp of their own.
+    started_at: Instant,
+    // Whether `read_avr_frames` has already logged the "no timestamp,
+    // falling back to local time" warning for this `AvrState`. Set once
+    // rather than logged on every timestamp-less line, since a `*`-only
+    // stream would otherwise spam it once per message.
+    warned_missing_timestamp: bool,
+}
+
+impl AvrState {
+    fn new(cfg: &Cli) -> io::Result<Self> {
+        let address_filter = match &cfg.address_filter {
+            Some(spec) => AddressFilter::from_spec(spec).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+            None => AddressFilter::default(),
+        };
+        Ok(AvrState {
+            cpr: CprDecoder::new().with_receiver_location(cfg.lat, cfg.lon, cfg.max_range_km),
+            dedup: DedupWindow::new(),
+            stats: Stats::new(),
+            quality: Quality::new(),
+            stats_logged_at: Instant::now(),
+            processed: 0,
+            address_filter,
+            started_at: Instant::now(),
+            warned_missing_timestamp: false,
+        })
+    }
+}
+
+/// Connects to `cfg.input` (a local receiver such as dump1090), reads
+/// Mode S frames in `cfg.input_format`, and logs each one, reconnecting
+/// with exponential backoff (via `connect_with_backoff`) whenever the
+/// connection drops or can't be established. Runs until `shutdown` is
+/// set. Does nothing if `--input` was not given.
+///
+/// `broadcaster`, if given, receives a line for every AVR message, encoded
+/// per `broadcaster.format`: SBS-1 (only for messages with a position,
+/// callsign, or velocity to report) for `--output sbs:<port>`, or one JSON
+/// object per message (regardless of what it decoded) for `--output json`.
+/// The Beast path only decodes as far as `libbeast::Frame` (an
+/// `adsb_deku` frame, not this crate's own `modes::ModesMessage`), so it
+/// doesn't feed either encoder yet.
+///
+/// Both paths run their frame timestamps through a `TimestampJumpDetector`
+/// per connection, and log any `DF_EVENT_TIMESTAMP_JUMP` it reports; this
+/// is how the upstream client notices the receiver's clock has skipped.
+///
+/// The AVR path also runs each decoded message through a `DedupWindow`
+/// before broadcasting it, so the same transmission picked up more than
+/// once (e.g. by multiple receivers feeding the same `--input` stream)
+/// isn't forwarded twice, and records it into a `Stats` and a `Quality`,
+/// logging (and, for `Stats` only, resetting) a summary every
+/// `cfg.stats_interval` seconds (disabled if that's 0).
+///
+/// If `cfg.max_messages` is set, the AVR path stops (logging the final
+/// `Stats` and setting `shutdown`, so the rest of the client exits too)
+/// once it has decoded that many messages. Useful for deterministic
+/// integration tests against a recorded stream.
+///
+/// If `cfg.input_file` is set, this replays that file instead of
+/// connecting to `cfg.input`: it runs the same decode pipeline once over
+/// the file and returns at EOF rather than looping/reconnecting. Set
+/// `cfg.replay_realtime` to pace the replay to the frames' recorded
+/// timestamps instead of running through the file as fast as possible.
+///
+/// If `cfg.record_file` is set, every raw byte read (from `cfg.input` or
+/// `cfg.input_file`) is also appended to that file via `TeeReader`, so a
+/// problematic live stream can be captured for later replay or a bug
+/// report without interrupting live decoding.
+///
+/// The AVR path's `CprDecoder` is also given the receiver's own location
+/// (`cfg.lat`/`cfg.lon`) and `cfg.max_range_km`, so a locally-decoded fix
+/// implausibly far from the receiver is dropped rather than handed to
+/// `broadcaster`.
+///
+/// If `cfg.filter_df` and/or `cfg.address_filter` are set, the AVR path
+/// drops any decoded message whose `df`/`address` doesn't pass, before any
+/// further processing (dedup, stats, `broadcaster`) sees it.
+///
+/// If `cfg.no_crc_check` is set, the AVR path builds messages with
+/// `ModesMessage::from_buffer_trusted` instead of `from_buffer`, skipping
+/// CRC validity checks and trusting length alone -- a throughput win for a
+/// source that has already validated (or stripped/overlaid) its own CRC,
+/// at the cost of letting corrupted frames through on a noisier one. Logs
+/// a warning once, at startup, when this is set.
+///
+/// A `*`-prefixed AVR line carries no timestamp of its own; the AVR path
+/// substitutes a local monotonic tick count (time since the connection's
+/// `AvrState` was created, scaled to the 12MHz Beast tick rate) so such a
+/// line still gets a `TimestampJumpDetector`/`--replay-realtime`-usable
+/// timestamp. This degrades multilateration sync quality since it's not
+/// the receiver's own clock, so the first such line on a connection logs
+/// a warning.
+pub fn run(cfg: &Cli, shutdown: Arc<AtomicBool>, broadcaster: Option<Broadcaster>) -> io::Result<()> {
+    if cfg.no_crc_check {
+        warn!("input: --no-crc-check is set, AVR messages will be trusted based on length alone; only use this against a source that has already validated its own CRC");
+    }
+
+    if let Some(path) = &cfg.input_file {
+        return replay_file(cfg, path, &shutdown, &broadcaster);
+    }
+
+    let Some(addr) = &cfg.input else {
+        return Ok(());
+    };
+
+    let mut avr_state = AvrState::new(cfg)?;
+    while !shutdown.load(Ordering::SeqCst) {
+        let stream = connect_with_backoff(addr, MAX_RECONNECT_BACKOFF);
+        stream.set_read_timeout(Some(SHUTDOWN_POLL_INTERVAL))?;
+        let source = maybe_tee(stream, cfg.record_file.as_deref());
+        // A fresh detector per connection: the gap while reconnected
+        // isn't a clock discontinuity worth reporting, just time the
+        // client wasn't listening.
+        let mut jump_detector = TimestampJumpDetector::new();
+        let result = match cfg.input_format.as_str() {
+            "beast" => read_beast_frames(source, &shutdown, &mut jump_detector, false),
+            "avr" => read_avr_frames(cfg, source, &shutdown, &broadcaster, &mut avr_state, &mut jump_detector, false),
+            other => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("unknown input format `{}`", other))),
+        };
+        if let Err(e) = result {
+            warn!("input connection to {} lost: {}", addr, e);
+        }
+    }
+
+    Ok(())
+}
+
+fn replay_file(cfg: &Cli, path: &str, shutdown: &Arc<AtomicBool>, broadcaster: &Option<Broadcaster>) -> io::Result<()> {
+    let file = File::open(path)?;
+    let source = maybe_tee(file, cfg.record_file.as_deref());
+    let mut jump_detector = TimestampJumpDetector::new();
+    match cfg.input_format.as_str() {
+        "beast" => read_beast_frames(source, shutdown, &mut jump_detector, cfg.replay_realtime),
+        "avr" => {
+            let mut avr_state = AvrState::new(cfg)?;
+            let result = read_avr_frames(cfg, source, shutdown, broadcaster, &mut avr_state, &mut jump_detector, cfg.replay_realtime);
+            info!("input: replay of {} finished, final stats: {}", path, avr_state.stats);
+            result
+        }
+        other => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("unknown input format `{}`", other))),
+    }
+}
+
+// How long to sleep to space two replayed frames `previous_ticks` and
+// `current_ticks` (12MHz timestamps) apart in real time. Doesn't account
+// for a 48-bit clock rollover mid-file (see
+// `modes::modes_message::TIMESTAMP_EPOCH_TICKS`) -- an edge case rare
+// enough within a single capture that a replay tool doesn't need to
+// handle it, unlike the live `TimestampJumpDetector`.
+fn replay_delay(previous_ticks: u64, current_ticks: u64) -> Duration {
+    let delta_ticks = current_ticks.saturating_sub(previous_ticks);
+    Duration::from_secs_f64(delta_ticks as f64 / REPLAY_CLOCK_HZ)
+}
+
+fn read_beast_frames<R: Read>(
+    source: R,
+    shutdown: &Arc<AtomicBool>,
+    jump_detector: &mut TimestampJumpDetector,
+    realtime: bool,
+) -> io::Result<()> {
+    let mut previous_timestamp = None;
+    let mut consecutive_zero_timestamps: u64 = 0;
+    for frame in libbeast::frames(source) {
+        if shutdown.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        match frame {
+            Ok(frame) => {
+                if realtime {
+                    if let Some(previous) = previous_timestamp {
+                        thread::sleep(replay_delay(previous, frame.timestamp));
+                    }
+                    previous_timestamp = Some(frame.timestamp);
+                }
+                info!("input: {}", frame.to_string());
+                if let Some(event) = jump_detector.update(frame.timestamp) {
+                    warn!("input: {}", event);
+                }
+                if frame.timestamp == 0 {
+                    consecutive_zero_timestamps += 1;
+                    if consecutive_zero_timestamps == ZERO_TIMESTAMP_WARN_THRESHOLD {
+                        warn!(
+                            "input: {} consecutive frames with an all-zero timestamp; this receiver doesn't appear to be timestamping, multilateration will not work",
+                            ZERO_TIMESTAMP_WARN_THRESHOLD
+                        );
+                    }
+                } else {
+                    consecutive_zero_timestamps = 0;
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+fn read_avr_frames<R: Read>(
+    cfg: &Cli,
+    source: R,
+    shutdown: &Arc<AtomicBool>,
+    broadcaster: &Option<Broadcaster>,
+    avr_state: &mut AvrState,
+    jump_detector: &mut TimestampJumpDetector,
+    realtime: bool,
+) -> io::Result<()> {
+    let mut reader = BufReader::new(source);
+    let mut line = String::new();
+    let encoder = broadcaster.as_ref().map(|b| b.format.encoder());
+    let mut previous_timestamp = None;
+    loop {
+        if shutdown.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        maybe_log_stats(cfg, avr_state);
+
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) => return Ok(()),
+            Ok(_) => {
+                if !libbeast::avr::line_has_timestamp(&line) && !avr_state.warned_missing_timestamp {
+                    warn!("input: AVR line has no timestamp, substituting local time; multilateration sync quality will suffer");
+                    avr_state.warned_missing_timestamp = true;
+                }
+                let local_ticks = (avr_state.started_at.elapsed().as_secs_f64() * REPLAY_CLOCK_HZ) as u64;
+                match libbeast::avr::parse_avr_line(
+                    &line,
+                    libbeast::avr::TimestampSource::LocalMonotonic(local_ticks),
+                    cfg.no_crc_check,
+                ) {
+                    Ok(message) => {
+                        if cfg.filter_df.as_ref().is_some_and(|dfs| !dfs.contains(&message.df)) {
+                            continue;
+                        }
+                        if !avr_state.address_filter.permits(message.address) {
+                            continue;
+                        }
+                        if realtime {
+                            if let Some(previous) = previous_timestamp {
+                                thread::sleep(replay_delay(previous, message.timestamp));
+                            }
+                            previous_timestamp = Some(message.timestamp);
+                        }
+                        info!("input: DF{} {:06X}", message.df, message.address);
+                        if let Some(event) = jump_detector.update(message.timestamp) {
+                            warn!("input: {}", event);
+                        }
+                        avr_state.stats.record(&message);
+                        avr_state.quality.record(&message);
+                        avr_state.processed += 1;
+                        if !avr_state.dedup.observe(&message) {
+                            continue;
+                        }
+                        if let (Some(broadcaster), Some(encoder)) = (broadcaster, &encoder) {
+                            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+                            let position = avr_state.cpr.update(&message, now);
+                            if let Some(line) = encoder.encode(&message, position) {
+                                broadcaster.send(&line);
+                            }
+                        }
+                        if cfg.max_messages.is_some_and(|max| avr_state.processed >= max) {
+                            info!("input: reached --max-messages, final stats: {}", avr_state.stats);
+                            shutdown.store(true, Ordering::SeqCst);
+                            return Ok(());
+                        }
+                    }
+                    Err(e) => warn!("could not parse AVR line `{}`: {}", line.trim_end(), e),
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// Logs and resets `avr_state.stats` once `cfg.stats_interval` seconds have
+// passed since the last log (checked on every loop iteration, including the
+// `SHUTDOWN_POLL_INTERVAL` read timeouts, so a quiet receiver still reports
+// on schedule). `cfg.stats_interval == 0` disables this. `avr_state.quality`
+// is logged alongside it but, unlike `stats`, isn't reset -- its rates
+// already describe a rolling window rather than an accumulating total.
+fn maybe_log_stats(cfg: &Cli, avr_state: &mut AvrState) {
+    if cfg.stats_interval == 0 {
+        return;
+    }
+    if avr_state.stats_logged_at.elapsed() < Duration::from_secs(cfg.stats_interval) {
+        return;
+    }
+    info!("input stats: {}", avr_state.stats);
+    let quality = avr_state.quality.snapshot();
+    info!(
+        "input quality: {:.1} msg/s, {:.1} pos/s, {:.0}% valid",
+        quality.message_rate,
+        quality.position_rate,
+        quality.valid_fraction * 100.0
+    );
+    avr_state.stats.reset();
+    avr_state.stats_logged_at = Instant::now();
+}
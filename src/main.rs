@@ -1,13 +1,23 @@
 extern crate log;
 
 use clap::{command, Parser, ArgGroup};
+use modes::modes_cpr::DEFAULT_MAX_RANGE_KM;
 use log::{LevelFilter};
 use log::{trace, debug, info, warn, error};
 use env_logger::Builder;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+mod address_filter;
+mod client;
+mod compress;
+mod input;
+mod output;
+mod reconnect;
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, after_help = "Use --capabilities to print supported input/output formats, compression modes, and decode features as JSON and exit, without needing --lat/--lon/--alt/--user/--server.")]
 //#[group(args = ["lat", "lon", "alt"], required = false)]
 #[clap(group(
     clap::ArgGroup::new("Receiver location")
@@ -23,16 +33,16 @@ use std::str::FromStr;
 ))]
 struct Cli {
   // Sets the latitude
-  #[arg(short = None, long = "lat", action, env = "MLAT_LAT", help = "Latitude of the receiver, in decimal degrees. Required.")]
-  lat: u32,
+  #[arg(short = None, long = "lat", action, env = "MLAT_LAT", value_parser = parse_latitude, help = "Latitude of the receiver, in decimal degrees. Required.")]
+  lat: f64,
 
   // Sets the longitude
-  #[arg(short = None, long = "lon", action, env = "MLAT_LON", help = "Longitude of the receiver, in decimal degrees. Required.")]
-  lon: u32,
+  #[arg(short = None, long = "lon", action, env = "MLAT_LON", value_parser = parse_longitude, help = "Longitude of the receiver, in decimal degrees. Required.")]
+  lon: f64,
 
   // Sets the altitude
-  #[arg(short = None, long = "alt", action, env = "MLAT_ALT", help = "Altitude of the receiver (height above ellipsoid). Required. Defaults to metres, but units may be specified with a 'ft' or 'm' suffix. (Except if they're negative).")]
-  alt: u32,
+  #[arg(short = None, long = "alt", action, env = "MLAT_ALT", value_parser = parse_altitude, help = "Altitude of the receiver (height above ellipsoid), in metres. Required. Units may be given with a 'ft' or 'm' suffix; bare numbers (including negative ones) are metres.")]
+  alt: f64,
 
   // Sets the privacy flag
   #[arg(short = None, long = "privacy", action = clap::ArgAction::SetTrue, default_value_t = false, env = "MLAT_PRIVACY", help = "Sets the privacy flag for this receiver. Currently, this removes the receiver location pin from the coverage maps.")]
@@ -42,30 +52,258 @@ struct Cli {
   #[arg(short = None, long = "user", action, env = "MLAT_USER", help = "User information to give to the server. Used to get in touch if there are problems.")]
   user: String,
 
-  // Sets the server 
-  #[arg(short = None, long = "server", action, env = "MLAT_SERVER", help = "host:port of the multilateration of the server to connect to")]
-  server: String,
+  // Sets the server
+  #[arg(short = None, long = "server", action, env = "MLAT_SERVER", value_parser = parse_server_addr, help = "host:port (or [ipv6]:port) of the multilateration server to connect to")]
+  server: (String, u16),
 
-  // Sets the no UDP flag
-  #[arg(short = None, long = "no-udp", action = clap::ArgAction::SetFalse, default_value_t = true, env = "MLAT_NO_UDP", help = "Don't offer to use UDP transport for sync/mlat messages")]
+  // Sets the no UDP flag. Off (UDP offered) by default; passing --no-udp
+  // turns it on and disables the UDP transport for sync/mlat messages.
+  #[arg(short = None, long = "no-udp", action = clap::ArgAction::SetTrue, default_value_t = false, env = "MLAT_NO_UDP", help = "Don't offer to use UDP transport for sync/mlat messages")]
   no_udp: bool,
 
+  // Sets the input source
+  #[arg(short = None, long = "input", action, env = "MLAT_INPUT", help = "host:port of a local receiver (e.g. dump1090) to read Mode S frames from")]
+  input: Option<String>,
+
+  // Sets a file to replay instead of a live --input connection
+  #[arg(short = None, long = "input-file", alias = "inputfile", action, env = "MLAT_INPUT_FILE", help = "Path to a recorded capture (in --input-format) to replay instead of connecting to --input. Runs once and exits at EOF.")]
+  input_file: Option<String>,
+
+  // Sets the input format
+  #[arg(short = None, long = "input-format", alias = "inputformat", action = clap::ArgAction::Set, default_value_t = String::from("beast"), value_parser = ["beast", "avr"], env = "MLAT_INPUT_FORMAT", help = "Format that --input/--input-file is speaking")]
+  input_format: String,
+
+  // Sets whether --input-file replay is paced to the recorded timestamps
+  #[arg(short = None, long = "replay-realtime", alias = "replayrealtime", action = clap::ArgAction::SetTrue, default_value_t = false, env = "MLAT_REPLAY_REALTIME", help = "When replaying --input-file, sleep between frames to match their recorded timestamps instead of replaying as fast as possible")]
+  replay_realtime: bool,
+
+  // Sets a file to tee the raw input bytes to, for later replay/bug reports
+  #[arg(short = None, long = "record-file", alias = "recordfile", action, env = "MLAT_RECORD_FILE", help = "Path to append the raw bytes read from --input to, alongside decoding them live. Useful for capturing a problematic stream to attach to a bug report.")]
+  record_file: Option<String>,
+
+  // Sets an output sink
+  #[arg(short = None, long = "output", action, env = "MLAT_OUTPUT", value_parser = parse_output, help = "format:port to serve decoded messages over TCP in (e.g. sbs:31003 for SBS-1 BaseStation format), or a bare format to write to stdout instead (e.g. json for newline-delimited JSON)")]
+  output: Option<String>,
+
+  // Sets the stats logging interval
+  #[arg(short = None, long = "stats-interval", alias = "statsinterval", action, env = "MLAT_STATS_INTERVAL", default_value_t = 60, help = "Log a summary of message decode stats every N seconds. 0 disables stats logging.")]
+  stats_interval: u64,
+
+  // Sets a bound on how many AVR input messages to process before exiting
+  #[arg(short = None, long = "max-messages", alias = "maxmessages", action, env = "MLAT_MAX_MESSAGES", help = "Stop cleanly after decoding this many --input messages, logging the final Stats. Useful for deterministic test/replay runs; unset runs forever.")]
+  max_messages: Option<u64>,
+
+  // Sets the CPR range gate against the receiver location
+  #[arg(short = None, long = "max-range-km", alias = "maxrangekm", action, env = "MLAT_MAX_RANGE_KM", default_value_t = DEFAULT_MAX_RANGE_KM, help = "Reject CPR-decoded positions farther than this from the receiver location (--lat/--lon), in kilometres. Guards against a stale or mismatched reference producing a wildly wrong local-decode fix.")]
+  max_range_km: f64,
+
+  // Restricts the AVR input pipeline to a set of downlink formats
+  #[arg(short = None, long = "filter-df", alias = "filterdf", action, env = "MLAT_FILTER_DF", value_parser = parse_df_filter, help = "Comma-separated list of downlink formats (0-31) to process from --input/--input-file; messages with any other df are dropped before further processing or output. Unset processes every df.")]
+  filter_df: Option<Vec<u32>>,
 
-  // Manage debugging information
-  #[arg(short = 'v', long = "log-level", alias = "loglevel", action = clap::ArgAction::Set, default_value_t = String::from("info"), value_parser = ["off", "error", "warn", "info", "debug", "trace"], env = "BLADERF_ADSB_LOG_LEVEL", help = "Log level")]
-  log_level: String,
+  // Restricts the AVR input pipeline to an ICAO address allow/block list
+  #[arg(short = None, long = "address-filter", alias = "addressfilter", action, env = "MLAT_ADDRESS_FILTER", value_parser = parse_address_filter_spec, help = "Comma-separated ICAO address allow/block list applied to --input/--input-file after decoding: a bare hex address (e.g. 4840D6) allows it, a `-`-prefixed one blocks it, and `@path` reads more entries (one per line, same syntax, `#`-comments allowed) from a file. An address on the block list is always dropped; if any allow entries are given, only those addresses pass. Unset processes every address.")]
+  address_filter: Option<String>,
+
+  // Skips CRC validity checks on the AVR input pipeline, trusting length
+  // alone. Off (CRC checked) by default; passing --no-crc-check turns it
+  // on.
+  #[arg(short = None, long = "no-crc-check", alias = "nocrccheck", action = clap::ArgAction::SetTrue, default_value_t = false, env = "MLAT_NO_CRC_CHECK", help = "Skip CRC validity checks on --input/--input-file AVR messages, trusting length alone. Only safe for a trusted local source that has already validated (or stripped/overlaid) its own CRC; on a noisy or untrusted feed this will let corrupted frames through.")]
+  no_crc_check: bool,
+
+
+  // Explicit log level override. Takes precedence over -v/-q when set;
+  // see `resolve_log_level`.
+  #[arg(short = None, long = "log-level", alias = "loglevel", action = clap::ArgAction::Set, value_parser = ["off", "error", "warn", "info", "debug", "trace"], env = "BLADERF_ADSB_LOG_LEVEL", help = "Log level. Overrides -v/-q if given.")]
+  log_level: Option<String>,
+
+  // Raises the log level above the default (info) by one step per
+  // occurrence: -v is debug, -vv is trace. Ignored if --log-level is set.
+  #[arg(short = 'v', long = "verbose", action = clap::ArgAction::Count, help = "Increase log verbosity (-v: debug, -vv: trace). Ignored if --log-level is set.")]
+  verbose: u8,
+
+  // Lowers the log level below the default (info) by one step per
+  // occurrence: -q is warn, -qq is error, -qqq is off. Ignored if
+  // --log-level is set.
+  #[arg(short = 'q', long = "quiet", action = clap::ArgAction::Count, help = "Decrease log verbosity (-q: warn, -qq: error, -qqq: off). Ignored if --log-level is set.")]
+  quiet: u8,
   #[arg(short = None, long = "log-style", alias = "logstyle", action = clap::ArgAction::Set, default_value_t = String::from("auto"), value_parser = ["auto", "always", "never"], env = "BLADERF_ADSB_LOG_STYLE", help = "Manage color for log messages")]
   log_style: String,
 }
 
+fn parse_latitude(s: &str) -> Result<f64, String> {
+    let value: f64 = s.parse().map_err(|_| format!("`{}` is not a valid latitude", s))?;
+    if !(-90.0..=90.0).contains(&value) {
+        return Err(format!("latitude must be between -90 and 90 degrees, got {}", value));
+    }
+    Ok(value)
+}
+
+fn parse_longitude(s: &str) -> Result<f64, String> {
+    let value: f64 = s.parse().map_err(|_| format!("`{}` is not a valid longitude", s))?;
+    if !(-180.0..=180.0).contains(&value) {
+        return Err(format!("longitude must be between -180 and 180 degrees, got {}", value));
+    }
+    Ok(value)
+}
+
+// Strips an optional 'ft'/'m' suffix and returns the height above
+// ellipsoid in metres, converting from feet (x0.3048) when 'ft' is
+// given. A bare number, with no suffix, is assumed to already be
+// metres, and negative altitudes (below the ellipsoid) parse the same
+// as any other number.
+fn parse_altitude(s: &str) -> Result<f64, String> {
+    let trimmed = s.trim();
+    let (number, feet) = if let Some(stripped) = trimmed.strip_suffix("ft") {
+        (stripped, true)
+    } else if let Some(stripped) = trimmed.strip_suffix('m') {
+        (stripped, false)
+    } else {
+        (trimmed, false)
+    };
+
+    let value: f64 = number.trim().parse().map_err(|_| format!("`{}` is not a valid altitude", s))?;
+    Ok(if feet { value * 0.3048 } else { value })
+}
+
+// Validates an `--output` spec (`<format>:<port>` to serve over TCP, or a
+// bare `<format>` to write to stdout) without actually binding or opening
+// anything; `output::spawn` does the real parsing once the sink is
+// started.
+fn parse_output(s: &str) -> Result<String, String> {
+    match s.split_once(':') {
+        Some((format, port)) => {
+            if format != "sbs" {
+                return Err(format!("unknown output format `{}` (supported over TCP: sbs)", format));
+            }
+            port.parse::<u16>().map_err(|_| format!("`{}` is not a valid port", port))?;
+        }
+        None if s == "json" => {}
+        None => return Err(format!("`{}` must be `<format>:<port>`, or a bare `json` to write to stdout", s)),
+    }
+    Ok(s.to_string())
+}
+
+// Parses a `--filter-df` list (`"0,4,17"`) into the downlink formats it
+// names, rejecting anything outside the 5-bit DF range (0-31) so a typo
+// fails fast at startup instead of silently filtering out everything.
+fn parse_df_filter(s: &str) -> Result<Vec<u32>, String> {
+    s.split(',')
+        .map(|entry| {
+            let entry = entry.trim();
+            let df: u32 = entry.parse().map_err(|_| format!("`{}` is not a valid downlink format", entry))?;
+            if df > 31 {
+                return Err(format!("downlink format {} is out of range (must be 0-31)", df));
+            }
+            Ok(df)
+        })
+        .collect()
+}
+
+// Splits a `--server` value into `(host, port)` up front, so a typo
+// (missing/non-numeric port, an unterminated IPv6 bracket) fails fast at
+// startup rather than surfacing deep in `connect_with_backoff`. Accepts
+// plain `host:port` for a hostname or IPv4 address, and `[host]:port`
+// (the usual bracket convention) for an IPv6 address, which would
+// otherwise be ambiguous with the `:port` separator.
+fn parse_server_addr(s: &str) -> Result<(String, u16), String> {
+    let (host, port) = if let Some(rest) = s.strip_prefix('[') {
+        let (host, after) =
+            rest.split_once(']').ok_or_else(|| format!("`{}` has an unterminated `[` (IPv6 host must be `[addr]:port`)", s))?;
+        let port = after.strip_prefix(':').ok_or_else(|| format!("`{}` is missing `:port` after `]`", s))?;
+        (host, port)
+    } else {
+        s.rsplit_once(':').ok_or_else(|| format!("`{}` must be `host:port`", s))?
+    };
+
+    if host.is_empty() {
+        return Err(format!("`{}` has an empty host", s));
+    }
+    let port: u16 = port.parse().map_err(|_| format!("`{}` is not a valid port", port))?;
+    Ok((host.to_string(), port))
+}
+
+// Checks a `--address-filter` spec is well-formed without reading any
+// `@file` entries yet (that happens once, when `input::AvrState` is built,
+// so a missing file fails with a clear I/O error after logging is set up
+// rather than from inside clap's own error path). An inline hex entry is
+// still validated eagerly, so a typo fails fast at startup.
+fn parse_address_filter_spec(s: &str) -> Result<String, String> {
+    for entry in s.split(',') {
+        let entry = entry.trim();
+        if entry.is_empty() || entry.starts_with('@') {
+            continue;
+        }
+        let hex = entry.strip_prefix('-').unwrap_or(entry);
+        if hex.is_empty() || hex.len() > 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("`{}` is not a valid ICAO address (expected 1-6 hex digits)", entry));
+        }
+    }
+    Ok(s.to_string())
+}
+
+// Resolves the effective log level from --log-level and -v/-q. An explicit
+// --log-level always wins, since a user who reaches for it clearly wants
+// exact control; otherwise the level is the default (info) shifted up by
+// `verbose` steps and down by `quiet` steps, clamped at either end of the
+// off..=trace range rather than wrapping or panicking on an extreme count.
+fn resolve_log_level(log_level: Option<&str>, verbose: u8, quiet: u8) -> LevelFilter {
+    if let Some(level) = log_level {
+        return LevelFilter::from_str(level).unwrap();
+    }
+
+    const LEVELS: [LevelFilter; 6] =
+        [LevelFilter::Off, LevelFilter::Error, LevelFilter::Warn, LevelFilter::Info, LevelFilter::Debug, LevelFilter::Trace];
+    const DEFAULT_INDEX: i32 = 3; // Info
+
+    let index = (DEFAULT_INDEX + verbose as i32 - quiet as i32).clamp(0, LEVELS.len() as i32 - 1);
+    LEVELS[index as usize]
+}
+
+// The JSON `print_capabilities` prints, split out so a test can check its
+// content without capturing stdout.
+fn capabilities_json() -> String {
+    format!(
+        "{{\"input_formats\":[\"beast\",\"avr\"],\
+\"output_formats\":[\"sbs\",\"json\"],\
+\"compression_modes\":[\"none\",\"zlib\"],\
+\"decode_features\":{{\
+\"cpr_position\":true,\
+\"velocity\":true,\
+\"callsign\":true,\
+\"single_bit_correction\":true,\
+\"two_bit_correction\":true,\
+\"default_max_correctable_bits\":{}\
+}}}}",
+        modes::modes_crc::DEFAULT_MAX_CORRECTABLE_BITS,
+    )
+}
+
+// Prints what this build supports (input/output formats, compression
+// modes, decode features) as a single line of JSON, so an integration can
+// query a given binary's capabilities without reading its source. Handled
+// before `Cli::parse()` (see `main`) so it doesn't also require --lat/
+// --lon/--alt/--user/--server, unlike every other flag.
+fn print_capabilities() {
+    println!("{}", capabilities_json());
+}
+
 // References:
 // https://docs.rs/clap/latest/clap/enum.ArgAction.html
 fn main() {
+    // --capabilities is answered before the rest of argument parsing, so a
+    // user asking "what does this binary support" doesn't also have to
+    // supply --lat/--lon/--alt/--user/--server just to get past Cli::parse.
+    if std::env::args().any(|arg| arg == "--capabilities") {
+        print_capabilities();
+        return;
+    }
+
     let cli = Cli::parse();
 
     // setup logging
     let mut builder = Builder::new();
-    builder.filter_level(LevelFilter::from_str(cli.log_level.as_str()).unwrap());
+    builder.filter_level(resolve_log_level(cli.log_level.as_deref(), cli.verbose, cli.quiet));
     builder.parse_write_style(cli.log_style.as_str());
     builder.init();
 
@@ -79,8 +317,201 @@ fn main() {
     debug!("debug");
     trace!("trace");
 
-    //ctrlc::set_handler(move || {
-    //  debug!("received Ctrl+C!");
-    //})
-    //.expect("Error setting Ctrl-C handler");
+    let shutdown = Arc::new(AtomicBool::new(false));
+    {
+        let shutdown = Arc::clone(&shutdown);
+        ctrlc::set_handler(move || {
+            info!("received Ctrl+C, shutting down");
+            shutdown.store(true, Ordering::SeqCst);
+        })
+        .expect("Error setting Ctrl-C handler");
+    }
+
+    let broadcaster = cli.output.as_deref().map(|spec| match output::spawn(spec, Arc::clone(&shutdown)) {
+        Ok(broadcaster) => broadcaster,
+        Err(e) => {
+            error!("could not start output server for {}: {}", spec, e);
+            std::process::exit(1);
+        }
+    });
+
+    std::thread::scope(|scope| {
+        let input_shutdown = Arc::clone(&shutdown);
+        scope.spawn(|| {
+            if let Err(e) = input::run(&cli, input_shutdown, broadcaster) {
+                error!("input source failed: {}", e);
+            }
+        });
+
+        if let Err(e) = client::run(&cli, shutdown) {
+            error!("connection to {}:{} failed: {}", cli.server.0, cli.server.1, e);
+            std::process::exit(1);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_altitude_treats_a_bare_number_as_metres() {
+        assert_eq!(parse_altitude("100"), Ok(100.0));
+    }
+
+    #[test]
+    fn parse_altitude_accepts_an_explicit_metres_suffix() {
+        assert_eq!(parse_altitude("100m"), Ok(100.0));
+    }
+
+    #[test]
+    fn parse_altitude_converts_feet_to_metres() {
+        assert_eq!(parse_altitude("328ft"), Ok(328.0 * 0.3048));
+    }
+
+    #[test]
+    fn parse_altitude_accepts_a_negative_altitude() {
+        assert_eq!(parse_altitude("-5m"), Ok(-5.0));
+    }
+
+    #[test]
+    fn parse_df_filter_accepts_a_comma_separated_list() {
+        assert_eq!(parse_df_filter("0,4,17"), Ok(vec![0, 4, 17]));
+    }
+
+    #[test]
+    fn parse_df_filter_tolerates_whitespace_around_entries() {
+        assert_eq!(parse_df_filter(" 17 , 18 "), Ok(vec![17, 18]));
+    }
+
+    #[test]
+    fn parse_df_filter_rejects_a_non_numeric_entry() {
+        assert!(parse_df_filter("17,nope").is_err());
+    }
+
+    #[test]
+    fn parse_df_filter_rejects_a_df_outside_the_5_bit_range() {
+        assert!(parse_df_filter("32").is_err());
+    }
+
+    #[test]
+    fn parse_address_filter_spec_accepts_bare_and_dash_prefixed_hex_entries() {
+        assert_eq!(parse_address_filter_spec("ABCDEF,-123456"), Ok("ABCDEF,-123456".to_string()));
+    }
+
+    #[test]
+    fn parse_address_filter_spec_accepts_an_at_file_entry_without_reading_it() {
+        assert_eq!(parse_address_filter_spec("@/no/such/file.txt"), Ok("@/no/such/file.txt".to_string()));
+    }
+
+    #[test]
+    fn parse_address_filter_spec_rejects_a_non_hex_entry() {
+        assert!(parse_address_filter_spec("NOTHEX").is_err());
+    }
+
+    #[test]
+    fn parse_address_filter_spec_rejects_an_overlong_entry() {
+        assert!(parse_address_filter_spec("1234567").is_err());
+    }
+
+    #[test]
+    fn resolve_log_level_defaults_to_info_with_no_flags() {
+        assert_eq!(resolve_log_level(None, 0, 0), LevelFilter::Info);
+    }
+
+    #[test]
+    fn resolve_log_level_raises_the_level_per_verbose_occurrence() {
+        assert_eq!(resolve_log_level(None, 1, 0), LevelFilter::Debug);
+        assert_eq!(resolve_log_level(None, 2, 0), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn resolve_log_level_lowers_the_level_per_quiet_occurrence() {
+        assert_eq!(resolve_log_level(None, 0, 1), LevelFilter::Warn);
+        assert_eq!(resolve_log_level(None, 0, 2), LevelFilter::Error);
+        assert_eq!(resolve_log_level(None, 0, 3), LevelFilter::Off);
+    }
+
+    #[test]
+    fn resolve_log_level_clamps_instead_of_wrapping_at_the_extremes() {
+        assert_eq!(resolve_log_level(None, 10, 0), LevelFilter::Trace);
+        assert_eq!(resolve_log_level(None, 0, 10), LevelFilter::Off);
+    }
+
+    #[test]
+    fn resolve_log_level_prefers_an_explicit_log_level_over_verbose_and_quiet() {
+        assert_eq!(resolve_log_level(Some("error"), 5, 5), LevelFilter::Error);
+    }
+
+    #[test]
+    fn capabilities_json_is_a_single_balanced_json_object() {
+        let json = capabilities_json();
+        assert!(json.starts_with('{') && json.ends_with('}'));
+        assert_eq!(json.matches('{').count(), json.matches('}').count());
+    }
+
+    #[test]
+    fn parse_output_accepts_an_sbs_port_spec() {
+        assert_eq!(parse_output("sbs:31003"), Ok("sbs:31003".to_string()));
+    }
+
+    #[test]
+    fn parse_output_accepts_a_bare_json_spec() {
+        assert_eq!(parse_output("json"), Ok("json".to_string()));
+    }
+
+    #[test]
+    fn parse_output_rejects_an_unknown_tcp_format() {
+        assert!(parse_output("json:31003").is_err());
+    }
+
+    #[test]
+    fn parse_output_rejects_an_unknown_bare_format() {
+        assert!(parse_output("sbs").is_err());
+    }
+
+    #[test]
+    fn parse_output_rejects_a_non_numeric_port() {
+        assert!(parse_output("sbs:not-a-port").is_err());
+    }
+
+    #[test]
+    fn parse_server_addr_splits_a_plain_host_and_port() {
+        assert_eq!(parse_server_addr("mlat.example.com:31090"), Ok(("mlat.example.com".to_string(), 31090)));
+    }
+
+    #[test]
+    fn parse_server_addr_accepts_bracketed_ipv6_hosts() {
+        assert_eq!(parse_server_addr("[::1]:31090"), Ok(("::1".to_string(), 31090)));
+    }
+
+    #[test]
+    fn parse_server_addr_rejects_a_missing_port() {
+        assert!(parse_server_addr("mlat.example.com").is_err());
+    }
+
+    #[test]
+    fn parse_server_addr_rejects_a_non_numeric_port() {
+        assert!(parse_server_addr("mlat.example.com:http").is_err());
+    }
+
+    #[test]
+    fn parse_server_addr_rejects_an_unterminated_ipv6_bracket() {
+        assert!(parse_server_addr("[::1:31090").is_err());
+    }
+
+    #[test]
+    fn parse_server_addr_rejects_an_empty_host() {
+        assert!(parse_server_addr(":31090").is_err());
+    }
+
+    #[test]
+    fn capabilities_json_lists_every_supported_input_and_output_format() {
+        let json = capabilities_json();
+        assert!(json.contains("\"beast\""));
+        assert!(json.contains("\"avr\""));
+        assert!(json.contains("\"sbs\""));
+        assert!(json.contains("\"json\""));
+        assert!(json.contains("\"zlib\""));
+    }
 }
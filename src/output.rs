@@ -0,0 +1,165 @@
+/*
+ * Part of mlat-client-rust (https://github.com/tjmullicani/mlat-client-rust) - an ADS-B multilateration client.
+ * Based on mlat-client (https://github.com/mutability/mlat-client)
+ * Copyright 2023, Timothy Mullican <timothy.j.mullican@gmail.com>
+ * Copyright 2015, Oliver Jowett <oliver@mutability.co.uk>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::io::{self, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use log::{info, warn};
+
+use modes::modes_output::{FrameEncoder, JsonEncoder, SbsEncoder};
+
+// How often the accept loop wakes up to check `shutdown`, when there is
+// no incoming connection in the meantime.
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Which `FrameEncoder` an `--output` spec selected, so callers building
+/// lines for a `Broadcaster` know which one to run a message through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Sbs,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn encoder(self) -> Box<dyn FrameEncoder> {
+        match self {
+            OutputFormat::Sbs => Box::new(SbsEncoder),
+            OutputFormat::Json => Box::new(JsonEncoder),
+        }
+    }
+}
+
+// Where a `Broadcaster`'s lines actually go: out to every TCP client
+// connected to an `--output <format>:<port>` server, or straight to
+// stdout for a bare `--output json`, for quick ad-hoc piping into `jq`
+// without standing up a server and a separate client to connect to it.
+#[derive(Clone)]
+enum Sink {
+    Tcp(Arc<Mutex<Vec<std::net::TcpStream>>>),
+    Stdout,
+}
+
+/// Broadcasts formatted lines (SBS-1, JSON, ...) to wherever an
+/// `--output` spec pointed them: a server's connected clients, or stdout.
+/// Cheap to clone: it's just a handle onto the shared sink.
+#[derive(Clone)]
+pub struct Broadcaster {
+    sink: Sink,
+    pub format: OutputFormat,
+}
+
+impl Broadcaster {
+    /// Writes `line` (plus a trailing newline) to every connected TCP
+    /// client, dropping any whose connection has failed -- or, for a
+    /// stdout sink, to stdout, flushed immediately so lines show up live
+    /// when piped rather than waiting on a full buffer.
+    pub fn send(&self, line: &str) {
+        match &self.sink {
+            Sink::Tcp(clients) => {
+                let mut clients = clients.lock().unwrap();
+                clients.retain_mut(|stream| writeln!(stream, "{}", line).is_ok());
+            }
+            Sink::Stdout => {
+                let mut stdout = io::stdout().lock();
+                let _ = writeln!(stdout, "{}", line);
+                let _ = stdout.flush();
+            }
+        }
+    }
+}
+
+// An `--output` spec is either `<format>:<port>` (serve `format` to every
+// TCP client connecting on `port`) or a bare `<format>` (write `format`
+// lines to stdout instead). Only `sbs` makes sense as a server (virtual
+// radar displays speak it over TCP); only `json` makes sense on stdout
+// (piped into `jq` or a file, not connected to).
+enum Spec {
+    Tcp { port: u16 },
+    Stdout,
+}
+
+fn parse_spec(spec: &str) -> io::Result<(OutputFormat, Spec)> {
+    match spec.split_once(':') {
+        Some((format, port)) => {
+            if format != "sbs" {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown output format `{}` (supported over TCP: sbs)", format),
+                ));
+            }
+            let port = port
+                .parse()
+                .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, format!("`{}` is not a valid port", port)))?;
+            Ok((OutputFormat::Sbs, Spec::Tcp { port }))
+        }
+        None => {
+            if spec != "json" {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown output format `{}` (supported on stdout: json)", spec),
+                ));
+            }
+            Ok((OutputFormat::Json, Spec::Stdout))
+        }
+    }
+}
+
+/// Starts an `--output` sink from `spec`: `<format>:<port>` serves
+/// `format` to every TCP client connecting on `port` (currently only
+/// `sbs`), accepting connections in a background thread until `shutdown`
+/// is set; a bare `json` writes lines to stdout instead, with nothing to
+/// spawn. Either way, returns a `Broadcaster` to feed it lines.
+pub fn spawn(spec: &str, shutdown: Arc<AtomicBool>) -> io::Result<Broadcaster> {
+    let (format, kind) = parse_spec(spec)?;
+
+    let port = match kind {
+        Spec::Stdout => return Ok(Broadcaster { sink: Sink::Stdout, format }),
+        Spec::Tcp { port } => port,
+    };
+
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    listener.set_nonblocking(true)?;
+
+    info!("serving {} output on port {}", spec, port);
+
+    let clients: Arc<Mutex<Vec<std::net::TcpStream>>> = Arc::new(Mutex::new(Vec::new()));
+    let accept_clients = Arc::clone(&clients);
+    thread::spawn(move || {
+        while !shutdown.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, addr)) => {
+                    info!("output client connected from {}", addr);
+                    accept_clients.lock().unwrap().push(stream);
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(ACCEPT_POLL_INTERVAL),
+                Err(e) => {
+                    warn!("output server accept failed: {}", e);
+                    thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+            }
+        }
+    });
+
+    Ok(Broadcaster { sink: Sink::Tcp(clients), format })
+}
@@ -0,0 +1,87 @@
+/*
+ * Part of mlat-client-rust (https://github.com/tjmullicani/mlat-client-rust) - an ADS-B multilateration client.
+ * Based on mlat-client (https://github.com/mutability/mlat-client)
+ * Copyright 2023, Timothy Mullican <timothy.j.mullican@gmail.com>
+ * Copyright 2015, Oliver Jowett <oliver@mutability.co.uk>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ *  the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::net::TcpStream;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use log::{info, warn};
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Connects to `addr`, retrying forever with exponential backoff (doubling
+/// from an initial 1 second delay, capped at `max_delay`) until a
+/// connection succeeds. Each delay has up to +/-20% jitter added, so a
+/// fleet of clients that all lost the same connection at once (e.g. a
+/// server restart) don't all retry in lockstep. Logs each attempt and
+/// failure via the `log` macros.
+///
+/// Used by both the input (dump1090) and output (mlat-server) connections,
+/// so a network blip or a restart on either end doesn't kill the feeder.
+pub fn connect_with_backoff(addr: &str, max_delay: Duration) -> TcpStream {
+    let mut backoff = INITIAL_BACKOFF;
+    loop {
+        info!("connecting to {}", addr);
+        match TcpStream::connect(addr) {
+            Ok(stream) => return stream,
+            Err(e) => warn!("could not connect to {} ({}), retrying in {:?}", addr, e, backoff),
+        }
+
+        thread::sleep(jittered(backoff));
+        backoff = (backoff * 2).min(max_delay);
+    }
+}
+
+// Adds up to +/-20% jitter to `delay`, seeded from the low bits of the
+// current time. This workspace has no RNG dependency, and the jitter
+// doesn't need to be unpredictable, just different across clients.
+fn jittered(delay: Duration) -> Duration {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().subsec_nanos();
+    let jitter_pct = (nanos % 41) as i64 - 20; // -20..=20
+    let delta_millis = delay.as_millis() as i64 * jitter_pct / 100;
+    let millis = (delay.as_millis() as i64 + delta_millis).max(0) as u64;
+    Duration::from_millis(millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jittered_stays_within_20_percent_of_the_requested_delay() {
+        let delay = Duration::from_secs(10);
+        for _ in 0..50 {
+            let jittered = jittered(delay);
+            assert!(
+                jittered.as_millis() >= 8_000 && jittered.as_millis() <= 12_000,
+                "{:?} outside +/-20% of {:?}",
+                jittered,
+                delay
+            );
+        }
+    }
+
+    #[test]
+    fn jittered_does_not_underflow_a_small_delay() {
+        // jitter_pct can be as negative as -20, so a naive `delay - delta`
+        // on a small enough `delay` could otherwise underflow.
+        assert!(jittered(Duration::from_millis(1)).as_millis() <= 1);
+    }
+}